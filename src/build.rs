@@ -1,24 +1,88 @@
 use crate::file::FileLoggerBuilder;
+#[cfg(unix)]
+use crate::journald::JournaldBuilder;
 use crate::misc;
+use crate::multi::MultiLoggerBuilder;
 use crate::null::NullLoggerBuilder;
 #[cfg(unix)]
 use crate::syslog::SyslogBuilder;
 use crate::terminal::TerminalLoggerBuilder;
 #[cfg(feature = "slog-kvfilter")]
 use crate::types::KVFilterParameters;
-use crate::types::{OverflowStrategy, Severity, SourceLocation};
+use crate::types::{LevelHandle, ModuleFilter, OverflowStrategy, Severity, SourceLocation};
 use crate::Result;
-use slog::{Drain, FnValue, Logger};
+use slog::{Drain, Filter, FnValue, Logger, OwnedKV, Record};
 use slog_async::Async;
 #[cfg(feature = "slog-kvfilter")]
 use slog_kvfilter::KVFilter;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
 use std::panic::{RefUnwindSafe, UnwindSafe};
 
 /// This trait allows to build a logger instance.
 pub trait Build {
     /// Builds a logger.
     fn build(&self) -> Result<Logger>;
+
+    /// Builds a logger together with a guard which waits for the logger to finish writing
+    /// log records when dropped.
+    ///
+    /// See the documentation of [`AsyncGuard`] for more details.
+    ///
+    /// The default implementation returns a guard which does nothing.
+    ///
+    /// [`AsyncGuard`]: ./struct.AsyncGuard.html
+    fn build_with_guard(&self) -> Result<(Logger, AsyncGuard)> {
+        let logger = track!(self.build())?;
+        Ok((logger, AsyncGuard::default()))
+    }
+}
+
+/// A guard which ensures that all log records issued to the associated logger are written.
+///
+/// Loggers built by this crate write log records asynchronously, so the records which are
+/// still queued are lost if the process exits (e.g., via `std::process::exit`) before they are
+/// written.
+/// When this guard is dropped (or [`flush`]ed), it blocks until all queued records are written
+/// and the underlying sink (e.g., a file or syslog) is flushed.
+///
+/// Note that log records issued after the guard is dropped are discarded.
+///
+/// # Examples
+///
+/// ```
+/// use slog::info;
+/// use sloggers::Build;
+/// use sloggers::terminal::TerminalLoggerBuilder;
+///
+/// let (logger, guard) = TerminalLoggerBuilder::new().build_with_guard().unwrap();
+/// info!(logger, "Hello World!");
+///
+/// // Waits until the above record is written before exiting.
+/// guard.flush();
+/// ```
+///
+/// [`flush`]: #method.flush
+#[derive(Default)]
+#[must_use]
+pub struct AsyncGuard {
+    guards: Vec<slog_async::AsyncGuard>,
+}
+impl AsyncGuard {
+    /// Blocks until all log records issued to the associated logger are written.
+    ///
+    /// This is equivalent to dropping the guard.
+    pub fn flush(self) {}
+
+    pub(crate) fn merge(&mut self, other: AsyncGuard) {
+        self.guards.extend(other.guards);
+    }
+}
+impl Debug for AsyncGuard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AsyncGuard")
+            .field("guards", &self.guards.len())
+            .finish()
+    }
 }
 
 /// Logger builder.
@@ -29,6 +93,13 @@ pub enum LoggerBuilder {
     /// File logger.
     File(FileLoggerBuilder),
 
+    /// systemd journal logger.
+    #[cfg(unix)]
+    Journald(JournaldBuilder),
+
+    /// Multi-destination logger.
+    Multi(MultiLoggerBuilder),
+
     /// Null logger.
     Null(NullLoggerBuilder),
 
@@ -43,12 +114,28 @@ impl Build for LoggerBuilder {
     fn build(&self) -> Result<Logger> {
         match *self {
             LoggerBuilder::File(ref b) => track!(b.build()),
+            #[cfg(unix)]
+            LoggerBuilder::Journald(ref b) => track!(b.build()),
+            LoggerBuilder::Multi(ref b) => track!(b.build()),
             LoggerBuilder::Null(ref b) => track!(b.build()),
             #[cfg(unix)]
             LoggerBuilder::Syslog(ref b) => track!(b.build()),
             LoggerBuilder::Terminal(ref b) => track!(b.build()),
         }
     }
+
+    fn build_with_guard(&self) -> Result<(Logger, AsyncGuard)> {
+        match *self {
+            LoggerBuilder::File(ref b) => track!(b.build_with_guard()),
+            #[cfg(unix)]
+            LoggerBuilder::Journald(ref b) => track!(b.build_with_guard()),
+            LoggerBuilder::Multi(ref b) => track!(b.build_with_guard()),
+            LoggerBuilder::Null(ref b) => track!(b.build_with_guard()),
+            #[cfg(unix)]
+            LoggerBuilder::Syslog(ref b) => track!(b.build_with_guard()),
+            LoggerBuilder::Terminal(ref b) => track!(b.build_with_guard()),
+        }
+    }
 }
 
 /// Common code for wrapping up a bare `Drain` into a finished `Logger`.
@@ -59,6 +146,9 @@ pub(crate) struct BuilderCommon {
     pub source_location: SourceLocation,
     pub overflow_strategy: OverflowStrategy,
     pub level: Severity,
+    pub filter: Option<ModuleFilter>,
+    pub level_handle: Option<LevelHandle>,
+    pub context: Vec<(String, String)>,
     pub channel_size: usize,
     #[cfg(feature = "slog-kvfilter")]
     pub kvfilterparameters: Option<KVFilterParameters>,
@@ -69,6 +159,9 @@ impl Default for BuilderCommon {
             source_location: SourceLocation::default(),
             overflow_strategy: OverflowStrategy::default(),
             level: Severity::default(),
+            filter: None,
+            level_handle: None,
+            context: Vec::new(),
             channel_size: 1024,
             #[cfg(feature = "slog-kvfilter")]
             kvfilterparameters: None,
@@ -76,18 +169,39 @@ impl Default for BuilderCommon {
     }
 }
 impl BuilderCommon {
-    pub fn build_with_drain<D>(&self, drain: D) -> Logger
+    /// Builds a logger which outputs log records to `drain`.
+    ///
+    /// If `with_guard` is `true`, an `AsyncGuard` for the logger is returned together.
+    /// Otherwise, the returned guard does nothing.
+    pub fn build_with_drain<D>(&self, drain: D, with_guard: bool) -> (Logger, AsyncGuard)
     where
         D: Drain + Send + 'static,
         D::Err: Debug,
     {
-        // async inside, level and key value filters outside for speed
-        let drain = Async::new(drain.fuse())
+        let builder = Async::new(drain.fuse())
             .chan_size(self.channel_size)
-            .overflow_strategy(self.overflow_strategy.to_async_type())
-            .build()
-            .fuse();
+            .overflow_strategy(self.overflow_strategy.to_async_type());
+        if with_guard {
+            let (drain, guard) = builder.build_with_guard();
 
+            // Records issued after the guard is dropped are discarded instead of panicking.
+            let logger = self.build_with_async_drain(drain.ignore_res());
+            let guard = AsyncGuard {
+                guards: vec![guard],
+            };
+            (logger, guard)
+        } else {
+            let logger = self.build_with_async_drain(builder.build().fuse());
+            (logger, AsyncGuard::default())
+        }
+    }
+
+    fn build_with_async_drain<D>(&self, drain: D) -> Logger
+    where
+        D: Drain + Send + Sync + UnwindSafe + RefUnwindSafe + 'static,
+        D::Err: Debug,
+    {
+        // async inside, level and key value filters outside for speed
         #[cfg(feature = "slog-kvfilter")]
         {
             if let Some(ref p) = self.kvfilterparameters {
@@ -111,9 +225,39 @@ impl BuilderCommon {
         D: Drain + Send + Sync + UnwindSafe + RefUnwindSafe + 'static,
         D::Err: Debug,
     {
-        let drain = self.level.set_level_filter(drain.fuse());
+        match (self.filter.clone(), self.level_handle.clone()) {
+            (Some(filter), Some(handle)) => {
+                let drain = Filter::new(drain.fuse(), move |record: &Record| {
+                    filter.is_enabled(record, handle.get())
+                });
+                self.build_root_logger(drain)
+            }
+            (Some(filter), None) => {
+                let level = self.level;
+                let drain = Filter::new(drain.fuse(), move |record: &Record| {
+                    filter.is_enabled(record, level)
+                });
+                self.build_root_logger(drain)
+            }
+            (None, Some(handle)) => {
+                let drain = Filter::new(drain.fuse(), move |record: &Record| {
+                    record.level().is_at_least(handle.get().as_level())
+                });
+                self.build_root_logger(drain)
+            }
+            (None, None) => {
+                let drain = self.level.set_level_filter(drain.fuse());
+                self.build_root_logger(drain)
+            }
+        }
+    }
 
-        match self.source_location {
+    fn build_root_logger<D>(&self, drain: D) -> Logger
+    where
+        D: Drain + Send + Sync + UnwindSafe + RefUnwindSafe + 'static,
+        D::Err: Debug,
+    {
+        let logger = match self.source_location {
             SourceLocation::None => Logger::root(drain.fuse(), o!()),
             SourceLocation::ModuleAndLine => {
                 Logger::root(drain.fuse(), o!("module" => FnValue(misc::module_and_line)))
@@ -125,6 +269,16 @@ impl BuilderCommon {
                 drain.fuse(),
                 o!("module" => FnValue(misc::local_file_and_line)),
             ),
+        };
+        if self.context.is_empty() {
+            logger
+        } else {
+            let context = self
+                .context
+                .iter()
+                .map(|(k, v)| (misc::intern_key(k), v.clone()))
+                .collect();
+            logger.new(OwnedKV(misc::ContextKV(context)))
         }
     }
 }
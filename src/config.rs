@@ -1,6 +1,11 @@
 #[cfg(not(unix))]
+use crate::fake_journald::JournaldNotSupported;
+#[cfg(not(unix))]
 use crate::fake_syslog::SyslogNotSupported;
 use crate::file::FileLoggerConfig;
+#[cfg(unix)]
+use crate::journald::JournaldConfig;
+use crate::multi::MultiLoggerConfig;
 use crate::null::NullLoggerConfig;
 #[cfg(unix)]
 use crate::syslog::SyslogConfig;
@@ -75,6 +80,29 @@ pub trait Config {
 /// let _config: LoggerConfig = serdeconv::from_toml_str(toml).unwrap();
 /// ```
 /// 
+/// Multi-destination logger.
+///
+/// ```
+/// extern crate sloggers;
+/// extern crate serdeconv;
+///
+/// use sloggers::LoggerConfig;
+///
+/// let toml = r#"
+/// type = "multi"
+///
+/// [[loggers]]
+/// type = "terminal"
+/// level = "info"
+///
+/// [[loggers]]
+/// type = "file"
+/// level = "debug"
+/// path = "/path/to/file.log"
+/// "#;
+/// let _config: LoggerConfig = serdeconv::from_toml_str(toml).unwrap();
+/// ```
+///
 /// Syslog logger. (Unix-like systems only.)
 /// 
 /// ```
@@ -99,6 +127,22 @@ pub trait Config {
 /// #     );
 /// # }
 /// ```
+///
+/// systemd journal logger. (Unix-like systems only.)
+///
+/// ```
+/// extern crate sloggers;
+/// extern crate serdeconv;
+///
+/// use sloggers::LoggerConfig;
+///
+/// let toml = r#"
+/// type = "journald"
+/// identifier = "my-app"
+/// "#;
+/// # #[cfg(unix)]
+/// let _config: LoggerConfig = serdeconv::from_toml_str(toml).unwrap();
+/// ```
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -106,6 +150,12 @@ pub trait Config {
 #[non_exhaustive]
 pub enum LoggerConfig {
     File(FileLoggerConfig),
+    #[cfg(unix)]
+    Journald(JournaldConfig),
+    #[cfg(not(unix))]
+    #[doc(hidden)]
+    Journald(JournaldNotSupported),
+    Multi(MultiLoggerConfig),
     Null(NullLoggerConfig),
     #[cfg(unix)]
     Syslog(SyslogConfig),
@@ -119,6 +169,15 @@ impl LoggerConfig {
     pub fn set_loglevel(&mut self, level: Severity) {
         match *self {
             LoggerConfig::File(ref mut c) => c.level = level,
+            #[cfg(unix)]
+            LoggerConfig::Journald(ref mut c) => c.level = level,
+            #[cfg(not(unix))]
+            LoggerConfig::Journald(_) => unreachable!(),
+            LoggerConfig::Multi(ref mut c) => {
+                for c in &mut c.loggers {
+                    c.set_loglevel(level);
+                }
+            }
             LoggerConfig::Null(_) => {}
             #[cfg(unix)]
             LoggerConfig::Syslog(ref mut c) => c.level = level,
@@ -133,6 +192,13 @@ impl Config for LoggerConfig {
     fn try_to_builder(&self) -> Result<Self::Builder> {
         match *self {
             LoggerConfig::File(ref c) => track!(c.try_to_builder()).map(LoggerBuilder::File),
+            #[cfg(unix)]
+            LoggerConfig::Journald(ref c) => {
+                track!(c.try_to_builder()).map(LoggerBuilder::Journald)
+            }
+            #[cfg(not(unix))]
+            LoggerConfig::Journald(_) => unreachable!(),
+            LoggerConfig::Multi(ref c) => track!(c.try_to_builder()).map(LoggerBuilder::Multi),
             LoggerConfig::Null(ref c) => track!(c.try_to_builder()).map(LoggerBuilder::Null),
             #[cfg(unix)]
             LoggerConfig::Syslog(ref c) => track!(c.try_to_builder()).map(LoggerBuilder::Syslog),
@@ -0,0 +1,16 @@
+#![cfg(not(unix))]
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+
+/// Fake journald configuration type, for platforms where journald is not
+/// supported. Cannot be constructed.
+#[derive(Clone, Debug, Serialize)]
+pub enum JournaldNotSupported {}
+
+impl<'de> Deserialize<'de> for JournaldNotSupported {
+    fn deserialize<D: Deserializer<'de>>(_: D) -> Result<Self, D::Error> {
+        Err(D::Error::custom(
+            "journald is not supported on this platform",
+        ))
+    }
+}
@@ -1,25 +1,38 @@
 //! File logger.
 use crate::build::BuilderCommon;
+use crate::logfmt::LogfmtDrain;
 use crate::misc;
 #[cfg(feature = "slog-kvfilter")]
 use crate::types::KVFilterParameters;
-use crate::types::{Format, OverflowStrategy, Severity, SourceLocation, TimeZone};
-use crate::{Build, Config, ErrorKind, Result};
-use chrono::{DateTime, Local, TimeZone as ChronoTimeZone, Utc};
+use crate::types::{
+    Format, LevelHandle, ModuleFilter, OverflowStrategy, Severity, SourceLocation, TimeZone,
+};
+use crate::{AsyncGuard, Build, Config, Error, ErrorKind, Result};
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime,
+    TimeZone as ChronoTimeZone, Timelike, Utc,
+};
 #[cfg(feature = "libflate")]
-use libflate::gzip::Encoder as GzipEncoder;
+use libflate::gzip::{EncodeOptions as GzipEncodeOptions, Encoder as GzipEncoder};
 use serde::{Deserialize, Serialize};
-use slog::Logger;
+use slog::{Drain, Level, Logger, OwnedKVList, Record};
 use slog_term::{CompactFormat, FullFormat, PlainDecorator};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
 use std::fmt::Debug;
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, BufWriter, Write};
+use std::mem;
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt};
 use std::path::{Path, PathBuf};
-#[cfg(feature = "libflate")]
-use std::sync::mpsc;
-#[cfg(feature = "libflate")]
+use std::result;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
+use trackable::error::ErrorKindExt;
 
 /// A logger builder which build loggers that write log records to the specified file.
 ///
@@ -30,6 +43,9 @@ pub struct FileLoggerBuilder {
     format: Format,
     timezone: TimeZone,
     appender: FileAppender,
+    path_template: Option<String>,
+    #[cfg(all(unix, feature = "signal-hook"))]
+    reopen_signal: Option<i32>,
 }
 
 impl FileLoggerBuilder {
@@ -43,6 +59,9 @@ impl FileLoggerBuilder {
             format: Format::default(),
             timezone: TimeZone::default(),
             appender: FileAppender::new(path),
+            path_template: None,
+            #[cfg(all(unix, feature = "signal-hook"))]
+            reopen_signal: None,
         }
     }
 
@@ -65,8 +84,13 @@ impl FileLoggerBuilder {
     }
 
     /// Sets the time zone which this logger will use.
+    ///
+    /// The time zone is also used for determining the boundaries of [`rotate_interval`].
+    ///
+    /// [`rotate_interval`]: ./struct.FileLoggerBuilder.html#method.rotate_interval
     pub fn timezone(&mut self, timezone: TimeZone) -> &mut Self {
         self.timezone = timezone;
+        self.appender.timezone = timezone;
         self
     }
 
@@ -76,6 +100,166 @@ impl FileLoggerBuilder {
         self
     }
 
+    /// Sets the handle for changing the log level of this logger at runtime.
+    ///
+    /// If this is specified, the log level set by [`level`] is ignored and
+    /// the current level of the handle is used instead.
+    ///
+    /// [`level`]: #method.level
+    pub fn level_handle(&mut self, handle: LevelHandle) -> &mut Self {
+        self.common.level_handle = Some(handle);
+        self
+    }
+
+    /// Sets the per-module log level filter of this logger.
+    ///
+    /// If this is specified, the log level set by [`level`] is only used for the modules
+    /// which match no directive of the filter (and only if the filter has no default level).
+    ///
+    /// [`level`]: #method.level
+    pub fn filter(&mut self, filter: ModuleFilter) -> &mut Self {
+        self.common.filter = Some(filter);
+        self
+    }
+
+    /// Adds a static key-value pair which is attached to all log records of this logger.
+    ///
+    /// This is typically used for attaching the service name or the host name.
+    ///
+    /// The value is used as it is. Placeholders like `{hostname}` are only expanded in
+    /// the `context` table of [`FileLoggerConfig`].
+    ///
+    /// [`FileLoggerConfig`]: ./struct.FileLoggerConfig.html#structfield.context
+    pub fn context(&mut self, key: &str, value: &str) -> &mut Self {
+        self.common.context.push((key.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Sets the permission bits of the log file (e.g., `0o640`).
+    ///
+    /// The bits are applied to the log file whenever it is opened
+    /// (regardless of the umask, and even if the file already exists),
+    /// and also to rotated and compressed files.
+    ///
+    /// By default, the permission bits are determined by the OS
+    /// (typically `0o666` masked by the umask).
+    ///
+    /// Changing the owner or the group of log files is out of the scope of this crate.
+    /// Run the process as the desired user, or set the set-group-ID bit of the log directory
+    /// so that new files inherit its group.
+    #[cfg(unix)]
+    pub fn file_mode(&mut self, mode: u32) -> &mut Self {
+        self.appender.file_mode = Some(mode);
+        self
+    }
+
+    /// Makes the logger create the missing parent directories of the log file.
+    ///
+    /// The default value is `false`.
+    pub fn create_dirs(&mut self, create: bool) -> &mut Self {
+        self.appender.create_dirs = create;
+        self
+    }
+
+    /// Sets the permission bits of the parent directories created by [`create_dirs`]
+    /// (e.g., `0o750`).
+    ///
+    /// [`create_dirs`]: ./struct.FileLoggerBuilder.html#method.create_dirs
+    #[cfg(unix)]
+    pub fn dir_mode(&mut self, mode: u32) -> &mut Self {
+        self.appender.dir_mode = Some(mode);
+        self
+    }
+
+    /// Enables templating of the path given to [`new`], and sets the format of `{timestamp}`
+    /// (see [strftime](https://docs.rs/chrono/0.4.6/chrono/format/strftime/index.html#specifiers)).
+    ///
+    /// Unless this is called, the path is used as it is. Otherwise, the following placeholders
+    /// in the path are replaced:
+    ///
+    /// - `{timestamp}`: the current time formatted according to `timestamp_format`
+    /// - `{date}`: the current date (e.g., `20180918`)
+    /// - `{hostname}`, `{pid}`, `{exe}` (the file name of the executable), and
+    ///   `${NAME}` (the value of the environment variable `NAME`)
+    ///
+    /// The time is represented in the time zone specified by [`timezone`].
+    /// The time-dependent placeholders are evaluated when the logger is built and each time
+    /// the log file is rotated, and the others only when the logger is built.
+    /// Building the logger fails if a placeholder cannot be expanded or is unknown
+    /// (a literal `{` must not appear in the path).
+    ///
+    /// When the log file is rotated, if the evaluated path differs from the current one,
+    /// the current file is left as it is (but compressed if [`rotate_compression`] is set)
+    /// and a new log file is opened at the evaluated path.
+    /// In that case, [`rotate_keep`], [`rotate_max_age`] and [`rotate_max_total_size`] apply to
+    /// the files written by this logger in the same directory whose names match the file name
+    /// template (e.g., `foo_20180918_1019.log` or `foo_20180918_1019.log.1.gz` for
+    /// `foo_{timestamp}.log`). To apply them to the files left by previous runs too,
+    /// use [`rotate_cleanup_existing`].
+    /// Otherwise, the file is rotated as usual.
+    ///
+    /// [`new`]: ./struct.FileLoggerBuilder.html#method.new
+    /// [`timezone`]: ./struct.FileLoggerBuilder.html#method.timezone
+    /// [`rotate_compression`]: ./struct.FileLoggerBuilder.html#method.rotate_compression
+    /// [`rotate_keep`]: ./struct.FileLoggerBuilder.html#method.rotate_keep
+    /// [`rotate_max_age`]: ./struct.FileLoggerBuilder.html#method.rotate_max_age
+    /// [`rotate_max_total_size`]: ./struct.FileLoggerBuilder.html#method.rotate_max_total_size
+    /// [`rotate_cleanup_existing`]: ./struct.FileLoggerBuilder.html#method.rotate_cleanup_existing
+    pub fn enable_path_template(&mut self, timestamp_format: &str) -> &mut Self {
+        self.path_template = Some(timestamp_format.to_owned());
+        self
+    }
+
+    /// Makes the logger maintain a symbolic link at `path` which points to the current log file.
+    ///
+    /// This is useful when the log file path contains `{timestamp}`
+    /// (see [`FileLoggerConfig::path`]), because the link has a fixed path
+    /// (e.g., `tail -F /var/log/app/current.log`).
+    /// The link is replaced atomically whenever a new log file is opened.
+    ///
+    /// [`FileLoggerConfig::path`]: ./struct.FileLoggerConfig.html#structfield.path
+    #[cfg(unix)]
+    pub fn symlink<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.appender.symlink = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the policy of syncing the log file to the storage device.
+    ///
+    /// The default value is [`SyncPolicy::Never`].
+    ///
+    /// [`SyncPolicy::Never`]: ./enum.SyncPolicy.html#variant.Never
+    pub fn sync_policy(&mut self, policy: SyncPolicy) -> &mut Self {
+        self.appender.sync_policy = policy;
+        self
+    }
+
+    /// Sets the handle for making this logger reopen its log file.
+    ///
+    /// See the documentation of [`ReopenHandle`] for more details.
+    ///
+    /// [`ReopenHandle`]: ./struct.ReopenHandle.html
+    pub fn reopen_handle(&mut self, handle: ReopenHandle) -> &mut Self {
+        self.appender.reopen_handle = handle;
+        self
+    }
+
+    /// Makes the logger reopen its log file whenever the process receives `signal`
+    /// (e.g., `libc::SIGHUP`).
+    ///
+    /// This is similar to calling [`ReopenHandle::reopen_on_signal`] on the handle of
+    /// this logger when it is built, but the thread spawned for the logger exits and
+    /// the signal is unregistered when the logger is dropped (e.g., replaced by reloading).
+    ///
+    /// This is only available if the `signal-hook` feature is enabled.
+    ///
+    /// [`ReopenHandle::reopen_on_signal`]: ./struct.ReopenHandle.html#method.reopen_on_signal
+    #[cfg(all(unix, feature = "signal-hook"))]
+    pub fn reopen_signal(&mut self, signal: i32) -> &mut Self {
+        self.reopen_signal = Some(signal);
+        self
+    }
+
     /// Sets the size of the asynchronous channel of this logger.
     pub fn channel_size(&mut self, channel_size: usize) -> &mut Self {
         self.common.channel_size = channel_size;
@@ -115,6 +299,63 @@ impl FileLoggerBuilder {
         self
     }
 
+    /// Sets the wall-clock interval used for determining whether rotate the current log file.
+    ///
+    /// If a boundary of the interval (e.g., midnight for [`RotateInterval::Daily`]) is passed,
+    /// the current log file will be rotated before the next log record is written.
+    /// The boundaries are calculated in the time zone specified by [`timezone`].
+    ///
+    /// This can be combined with [`rotate_size`]. In that case, the file will be rotated
+    /// when either condition is met.
+    ///
+    /// The default value is [`RotateInterval::Never`].
+    ///
+    /// [`RotateInterval::Daily`]: ./enum.RotateInterval.html#variant.Daily
+    /// [`RotateInterval::Never`]: ./enum.RotateInterval.html#variant.Never
+    /// [`timezone`]: ./struct.FileLoggerBuilder.html#method.timezone
+    /// [`rotate_size`]: ./struct.FileLoggerBuilder.html#method.rotate_size
+    pub fn rotate_interval(&mut self, interval: RotateInterval) -> &mut Self {
+        self.appender.rotate_interval = interval;
+        self
+    }
+
+    /// Sets the naming scheme of rotated log files.
+    ///
+    /// If [`RotateNaming::Timestamp`] is specified, the name of a rotated file will be
+    /// `"${ORIGINAL_FILE_NAME}.${ROTATION_TIME}"` (e.g., `"foo.log.2018-09-18T10-19-51"`),
+    /// and previously rotated files will never be renamed.
+    /// The rotation time is formatted according to [`rotate_timestamp_template`].
+    ///
+    /// The default value is [`RotateNaming::Sequential`],
+    /// which is described in the documentation of [`rotate_size`].
+    ///
+    /// [`RotateNaming::Timestamp`]: ./enum.RotateNaming.html#variant.Timestamp
+    /// [`RotateNaming::Sequential`]: ./enum.RotateNaming.html#variant.Sequential
+    /// [`rotate_timestamp_template`]: ./struct.FileLoggerBuilder.html#method.rotate_timestamp_template
+    /// [`rotate_size`]: ./struct.FileLoggerBuilder.html#method.rotate_size
+    pub fn rotate_naming(&mut self, naming: RotateNaming) -> &mut Self {
+        self.appender.rotate_naming = naming;
+        self
+    }
+
+    /// Sets the format string for the rotation time in the names of rotated log files.
+    ///
+    /// The string is formatted using [strftime](https://docs.rs/chrono/0.4.6/chrono/format/strftime/index.html#specifiers),
+    /// and the time respects the [`timezone`] setting.
+    /// The resulting names must be able to be parsed back by the same format string,
+    /// because the oldest files are determined by the parsed times.
+    ///
+    /// This is only used if [`RotateNaming::Timestamp`] is specified.
+    ///
+    /// The default value is `"%Y-%m-%dT%H-%M-%S"`.
+    ///
+    /// [`timezone`]: ./struct.FileLoggerBuilder.html#method.timezone
+    /// [`RotateNaming::Timestamp`]: ./enum.RotateNaming.html#variant.Timestamp
+    pub fn rotate_timestamp_template(&mut self, template: &str) -> &mut Self {
+        self.appender.rotate_timestamp_template = template.to_owned();
+        self
+    }
+
     /// Sets the maximum number of rotated log files to keep.
     ///
     /// If the number of rotated log files exceed this value, the oldest log file will be deleted.
@@ -125,35 +366,290 @@ impl FileLoggerBuilder {
         self
     }
 
+    /// Sets the maximum age of rotated log files to keep.
+    ///
+    /// Rotated log files whose last modification time is older than this value will be deleted.
+    /// This is checked every time the current log file is rotated.
+    ///
+    /// By default, rotated log files are not deleted because of their age.
+    pub fn rotate_max_age(&mut self, age: Duration) -> &mut Self {
+        self.appender.rotate_max_age = Some(age);
+        self
+    }
+
+    /// Sets the maximum total byte size of rotated log files to keep.
+    ///
+    /// Every time the current log file is rotated, the oldest rotated log files will be deleted
+    /// until the total size of the remaining rotated files
+    /// (including the file being rotated, before compression) does not exceed this value.
+    /// The file being rotated is never deleted, even if its size alone exceeds this value.
+    ///
+    /// The default value is `std::u64::MAX`.
+    pub fn rotate_max_total_size(&mut self, size: u64) -> &mut Self {
+        self.appender.rotate_max_total_size = size;
+        self
+    }
+
+    /// Makes [`rotate_keep`], [`rotate_max_age`] and [`rotate_max_total_size`] also apply to
+    /// the log files which match the path template but were not written by this logger
+    /// (e.g., those left by previous runs of the process).
+    ///
+    /// This only matters if templating is enabled by [`enable_path_template`].
+    /// Note that any file matching the template is deleted, even if it was created by
+    /// another program.
+    ///
+    /// The default value is `false`.
+    ///
+    /// [`rotate_keep`]: ./struct.FileLoggerBuilder.html#method.rotate_keep
+    /// [`rotate_max_age`]: ./struct.FileLoggerBuilder.html#method.rotate_max_age
+    /// [`rotate_max_total_size`]: ./struct.FileLoggerBuilder.html#method.rotate_max_total_size
+    /// [`enable_path_template`]: ./struct.FileLoggerBuilder.html#method.enable_path_template
+    pub fn rotate_cleanup_existing(&mut self, cleanup: bool) -> &mut Self {
+        self.appender.rotate_cleanup_existing = cleanup;
+        self
+    }
+
     /// Sets whether to compress or not compress rotated files.
     ///
     /// If `true` is specified, rotated files will be compressed by GZIP algorithm and
     /// the suffix ".gz" will be appended to those file names.
     ///
+    /// This is a shorthand of `rotate_compression(Compression::Gzip)`
+    /// (or `rotate_compression(Compression::None)` if `false` is specified).
+    ///
     /// The default value is `false`.
     #[cfg(feature = "libflate")]
     pub fn rotate_compress(&mut self, compress: bool) -> &mut Self {
-        self.appender.rotate_compress = compress;
+        self.appender.rotate_compression = if compress {
+            Compression::Gzip
+        } else {
+            Compression::None
+        };
+        self
+    }
+
+    /// Sets the algorithm used for compressing rotated files.
+    ///
+    /// The suffix associated with the algorithm (e.g., ".gz" or ".zst") will be appended to
+    /// the names of compressed files.
+    /// Compression is done by a background thread.
+    ///
+    /// The default value is [`Compression::None`].
+    ///
+    /// [`Compression::None`]: ./enum.Compression.html#variant.None
+    pub fn rotate_compression(&mut self, compression: Compression) -> &mut Self {
+        self.appender.rotate_compression = compression;
+        self
+    }
+
+    /// Sets the compression level used for compressing rotated files.
+    ///
+    /// The meaning of the level depends on the algorithm specified by [`rotate_compression`]:
+    ///
+    /// - Gzip: `0` means no compression (stored blocks), and any other value means
+    ///   the default compression (`libflate` doesn't support finer-grained levels).
+    /// - Zstd: from `1` (fastest) to `22` (smallest). `0` means the default level (`3`).
+    ///
+    /// By default, the default level of each algorithm is used.
+    ///
+    /// [`rotate_compression`]: ./struct.FileLoggerBuilder.html#method.rotate_compression
+    pub fn rotate_compression_level(&mut self, level: i32) -> &mut Self {
+        self.appender.rotate_compression_level = Some(level);
         self
     }
 }
 
-impl Build for FileLoggerBuilder {
-    fn build(&self) -> Result<Logger> {
-        let decorator = PlainDecorator::new(self.appender.clone());
+impl FileLoggerBuilder {
+    fn build_logger(&self, with_guard: bool) -> Result<(Logger, AsyncGuard)> {
+        let mut appender = self.appender.clone();
+        track!(self.resolve_path(&mut appender))?;
+        #[cfg(all(unix, feature = "signal-hook"))]
+        if let Some(signal) = self.reopen_signal {
+            let signals = track!(appender.reopen_handle.spawn_signal_thread(signal))?;
+            appender.signals = Some(signals);
+        }
+        let sync_level = match self.appender.sync_policy {
+            SyncPolicy::OnLevel(severity) => Some(severity.as_level()),
+            _ => None,
+        };
+        let sync_requested = Arc::clone(&appender.sync_requested);
         let timestamp = misc::timezone_to_timestamp_fn(self.timezone);
         let logger = match self.format {
             Format::Full => {
+                let decorator = PlainDecorator::new(appender);
                 let format = FullFormat::new(decorator).use_custom_timestamp(timestamp);
-                self.common.build_with_drain(format.build())
+                let drain = SyncOnLevel::new(format.build(), sync_level, sync_requested);
+                self.common.build_with_drain(drain, with_guard)
             }
             Format::Compact => {
+                let decorator = PlainDecorator::new(appender);
                 let format = CompactFormat::new(decorator).use_custom_timestamp(timestamp);
-                self.common.build_with_drain(format.build())
+                let drain = SyncOnLevel::new(format.build(), sync_level, sync_requested);
+                self.common.build_with_drain(drain, with_guard)
+            }
+            Format::Json => {
+                let drain = misc::json_drain(appender, self.timezone);
+                let drain = SyncOnLevel::new(drain, sync_level, sync_requested);
+                self.common.build_with_drain(drain, with_guard)
             }
+            Format::Logfmt => {
+                let drain = LogfmtDrain::new(appender, self.timezone);
+                let drain = SyncOnLevel::new(drain, sync_level, sync_requested);
+                self.common.build_with_drain(drain, with_guard)
+            }
+        };
+        Ok(logger)
+    }
+
+    /// Resolves the placeholders in the log file path of `appender`, if templating is enabled.
+    fn resolve_path(&self, appender: &mut FileAppender) -> Result<()> {
+        let timestamp_format = match self.path_template {
+            Some(ref format) => format,
+            None => return Ok(()),
         };
+        let path = track_assert_some!(
+            appender.path.to_str(),
+            ErrorKind::Invalid,
+            "Non UTF-8 log file path: {:?}",
+            appender.path
+        );
+        let path = track!(misc::expand_placeholders(path))?;
+
+        // The remaining placeholders must be the time-dependent ones.
+        let mut rest = path.replace("{timestamp}", "").replace("{date}", "");
+        if let Some(start) = rest.find('{') {
+            rest.drain(..start);
+            track_panic!(
+                ErrorKind::Invalid,
+                "Unknown placeholder in the log file path {:?}: {:?}",
+                path,
+                rest
+            );
+        }
+        appender.set_path_template(path, timestamp_format.clone());
+        Ok(())
+    }
+}
+
+impl Build for FileLoggerBuilder {
+    fn build(&self) -> Result<Logger> {
+        let (logger, _) = track!(self.build_logger(false))?;
         Ok(logger)
     }
+
+    fn build_with_guard(&self) -> Result<(Logger, AsyncGuard)> {
+        track!(self.build_logger(true))
+    }
+}
+
+/// A handle for making file loggers reopen their log files.
+///
+/// This is useful when log files are rotated by external tools like `logrotate`:
+/// after a log file is moved, calling [`reopen`] makes the loggers create a new file at the
+/// original path instead of continuing to write to the moved file.
+///
+/// The handle can be cloned and shared among threads and loggers.
+///
+/// [`reopen`]: #method.reopen
+///
+/// # Examples
+///
+/// ```
+/// use sloggers::Build;
+/// use sloggers::file::{FileLoggerBuilder, ReopenHandle};
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let handle = ReopenHandle::new();
+/// let logger = FileLoggerBuilder::new(dir.path().join("foo.log"))
+///     .reopen_handle(handle.clone())
+///     .build()
+///     .unwrap();
+///
+/// // Called by an external rotation tool (e.g., via an admin endpoint or a signal).
+/// handle.reopen();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ReopenHandle(Arc<AtomicUsize>);
+impl ReopenHandle {
+    /// Makes a new `ReopenHandle` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes the associated loggers reopen their log files.
+    ///
+    /// The files are reopened just before the next log records are written.
+    pub fn reopen(&self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Spawns a thread which calls [`reopen`] whenever the process receives `signal`
+    /// (e.g., `libc::SIGHUP`).
+    ///
+    /// The thread and the signal registration last until the process exits.
+    /// To tie them to a logger instead, use [`FileLoggerBuilder::reopen_signal`].
+    ///
+    /// This is only available if the `signal-hook` feature is enabled.
+    ///
+    /// [`reopen`]: #method.reopen
+    /// [`FileLoggerBuilder::reopen_signal`]: ./struct.FileLoggerBuilder.html#method.reopen_signal
+    #[cfg(all(unix, feature = "signal-hook"))]
+    pub fn reopen_on_signal(&self, signal: i32) -> Result<()> {
+        track!(self.spawn_signal_thread(signal))?;
+        Ok(())
+    }
+
+    /// Spawns a thread which calls `reopen` whenever the process receives `signal`.
+    ///
+    /// The thread exits and the signal is unregistered when the returned handle is closed.
+    #[cfg(all(unix, feature = "signal-hook"))]
+    fn spawn_signal_thread(&self, signal: i32) -> Result<signal_hook::iterator::Handle> {
+        let mut signals =
+            track!(signal_hook::iterator::Signals::new([signal]).map_err(Error::from))?;
+        let signals_handle = signals.handle();
+        let handle = self.clone();
+        thread::spawn(move || {
+            for _ in signals.forever() {
+                handle.reopen();
+            }
+        });
+        Ok(signals_handle)
+    }
+
+    fn generation(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// A drain which requests `FileAppender` to sync the file after writing records
+/// whose level is at least `level`.
+struct SyncOnLevel<D> {
+    drain: D,
+    level: Option<Level>,
+    requested: Arc<AtomicBool>,
+}
+impl<D: Drain> SyncOnLevel<D> {
+    fn new(drain: D, level: Option<Level>, requested: Arc<AtomicBool>) -> Self {
+        SyncOnLevel {
+            drain,
+            level,
+            requested,
+        }
+    }
+}
+impl<D: Drain> Drain for SyncOnLevel<D> {
+    type Ok = D::Ok;
+    type Err = D::Err;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> result::Result<D::Ok, D::Err> {
+        if self
+            .level
+            .is_some_and(|level| record.level().is_at_least(level))
+        {
+            self.requested.store(true, Ordering::SeqCst);
+        }
+        self.drain.log(record, values)
+    }
 }
 
 #[derive(Debug)]
@@ -164,12 +660,37 @@ struct FileAppender {
     written_size: u64,
     rotate_size: u64,
     rotate_keep: usize,
-    #[cfg(feature = "libflate")]
-    rotate_compress: bool,
-    #[cfg(feature = "libflate")]
+    rotate_naming: RotateNaming,
+    rotate_timestamp_template: String,
+    rotate_max_age: Option<Duration>,
+    rotate_max_total_size: u64,
+    rotate_interval: RotateInterval,
+    next_rotation: Option<DateTime<Utc>>,
+    timezone: TimeZone,
+    in_record: bool,
+    rotate_compression: Compression,
+    rotate_compression_level: Option<i32>,
     wait_compression: Option<mpsc::Receiver<io::Result<()>>>,
     next_reopen_check: Instant,
     reopen_check_interval: Duration,
+    reopen_handle: ReopenHandle,
+    reopen_generation: usize,
+    /// The handle of the thread spawned by `FileLoggerBuilder::reopen_signal`.
+    #[cfg(all(unix, feature = "signal-hook"))]
+    signals: Option<signal_hook::iterator::Handle>,
+    sync_policy: SyncPolicy,
+    sync_pending: Arc<AtomicBool>,
+    syncer: Option<mpsc::Sender<File>>,
+    sync_requested: Arc<AtomicBool>,
+    file_mode: Option<u32>,
+    create_dirs: bool,
+    dir_mode: Option<u32>,
+    symlink: Option<PathBuf>,
+    path_template: Option<String>,
+    timestamp_template: String,
+    /// The previous paths evaluated from `path_template`, whose files may still exist.
+    template_paths: Vec<PathBuf>,
+    rotate_cleanup_existing: bool,
 }
 
 impl Clone for FileAppender {
@@ -181,12 +702,35 @@ impl Clone for FileAppender {
             written_size: 0,
             rotate_size: self.rotate_size,
             rotate_keep: self.rotate_keep,
-            #[cfg(feature = "libflate")]
-            rotate_compress: self.rotate_compress,
-            #[cfg(feature = "libflate")]
+            rotate_naming: self.rotate_naming,
+            rotate_timestamp_template: self.rotate_timestamp_template.clone(),
+            rotate_max_age: self.rotate_max_age,
+            rotate_max_total_size: self.rotate_max_total_size,
+            rotate_interval: self.rotate_interval,
+            next_rotation: None,
+            timezone: self.timezone,
+            in_record: false,
+            rotate_compression: self.rotate_compression,
+            rotate_compression_level: self.rotate_compression_level,
             wait_compression: None,
             next_reopen_check: Instant::now(),
             reopen_check_interval: self.reopen_check_interval,
+            reopen_handle: self.reopen_handle.clone(),
+            reopen_generation: self.reopen_handle.generation(),
+            #[cfg(all(unix, feature = "signal-hook"))]
+            signals: None,
+            sync_policy: self.sync_policy,
+            sync_pending: Arc::new(AtomicBool::new(false)),
+            syncer: None,
+            sync_requested: Arc::new(AtomicBool::new(false)),
+            file_mode: self.file_mode,
+            create_dirs: self.create_dirs,
+            dir_mode: self.dir_mode,
+            symlink: self.symlink.clone(),
+            path_template: self.path_template.clone(),
+            timestamp_template: self.timestamp_template.clone(),
+            template_paths: Vec::new(),
+            rotate_cleanup_existing: self.rotate_cleanup_existing,
         }
     }
 }
@@ -200,12 +744,35 @@ impl FileAppender {
             written_size: 0,
             rotate_size: default_rotate_size(),
             rotate_keep: default_rotate_keep(),
-            #[cfg(feature = "libflate")]
-            rotate_compress: false,
-            #[cfg(feature = "libflate")]
+            rotate_naming: RotateNaming::default(),
+            rotate_timestamp_template: default_rotate_timestamp_template(),
+            rotate_max_age: None,
+            rotate_max_total_size: default_rotate_max_total_size(),
+            rotate_interval: RotateInterval::default(),
+            next_rotation: None,
+            timezone: TimeZone::default(),
+            in_record: false,
+            rotate_compression: Compression::default(),
+            rotate_compression_level: None,
             wait_compression: None,
             next_reopen_check: Instant::now(),
             reopen_check_interval: Duration::from_millis(1000),
+            reopen_handle: ReopenHandle::new(),
+            reopen_generation: 0,
+            #[cfg(all(unix, feature = "signal-hook"))]
+            signals: None,
+            sync_policy: SyncPolicy::default(),
+            sync_pending: Arc::new(AtomicBool::new(false)),
+            syncer: None,
+            sync_requested: Arc::new(AtomicBool::new(false)),
+            file_mode: None,
+            create_dirs: false,
+            dir_mode: None,
+            symlink: None,
+            path_template: None,
+            timestamp_template: default_timestamp_template(),
+            template_paths: Vec::new(),
+            rotate_cleanup_existing: false,
         }
     }
 
@@ -225,11 +792,15 @@ impl FileAppender {
         };
 
         if self.file.is_none() || !path_exists {
+            if self.create_dirs {
+                self.create_parent_dirs()?;
+            }
             let mut file_builder = OpenOptions::new();
             file_builder.create(true);
             if self.truncate {
                 file_builder.truncate(true);
             }
+            set_file_mode(&mut file_builder, self.file_mode);
             // If the old file was externally deleted and we attempt to open a new one before releasing the old
             // handle, we get a Permission denied on Windows. Release the handle.
             self.file = None;
@@ -237,142 +808,556 @@ impl FileAppender {
                 .append(!self.truncate)
                 .write(true)
                 .open(&self.path)?;
-            self.written_size = file.metadata()?.len();
+            // The mode given to `OpenOptions` is masked by the umask,
+            // and it is not applied to existing files.
+            #[cfg(unix)]
+            if let Some(mode) = self.file_mode {
+                file.set_permissions(fs::Permissions::from_mode(mode))?;
+            }
+            let metadata = file.metadata()?;
+            self.written_size = metadata.len();
+            if self.next_rotation.is_none() {
+                // If the file already contains records from a previous interval
+                // (e.g., written by a previous process), it will be rotated soon.
+                let last_modified = if self.written_size > 0 {
+                    metadata
+                        .modified()
+                        .map(DateTime::from)
+                        .unwrap_or_else(|_| Utc::now())
+                } else {
+                    Utc::now()
+                };
+                self.next_rotation = self
+                    .rotate_interval
+                    .next_boundary(self.timezone, last_modified);
+            }
+            self.start_periodic_sync(&file)?;
             self.file = Some(BufWriter::new(file));
+            self.update_symlink()?;
         }
         Ok(())
     }
 
-    fn rotate(&mut self) -> io::Result<()> {
-        #[cfg(feature = "libflate")]
-        {
-            if let Some(ref mut rx) = self.wait_compression {
-                use std::sync::mpsc::TryRecvError;
-                match rx.try_recv() {
-                    Err(TryRecvError::Empty) => {
-                        // The previous compression is in progress
-                        return Ok(());
-                    }
-                    Err(TryRecvError::Disconnected) => {
-                        let e = io::Error::new(
-                            io::ErrorKind::Other,
-                            "Log file compression thread aborted",
-                        );
-                        return Err(e);
-                    }
-                    Ok(result) => {
-                        result?;
-                    }
-                }
+    fn is_sync_needed(&mut self) -> bool {
+        match self.sync_policy {
+            SyncPolicy::Never => false,
+            SyncPolicy::EveryRecord => true,
+            SyncPolicy::Interval(_) => {
+                // The file is synced by the thread spawned in `start_periodic_sync`.
+                self.sync_pending.store(true, Ordering::SeqCst);
+                false
             }
-            self.wait_compression = None;
+            SyncPolicy::OnLevel(_) => self.sync_requested.swap(false, Ordering::SeqCst),
         }
+    }
 
-        let _ = self.file.take();
+    /// Makes the file be synced periodically, if `SyncPolicy::Interval` is specified.
+    ///
+    /// The syncing thread is spawned when the first file is opened, and it receives
+    /// a handle of every file opened afterwards.
+    fn start_periodic_sync(&mut self, file: &File) -> io::Result<()> {
+        if let SyncPolicy::Interval(interval) = self.sync_policy {
+            let file = file.try_clone()?;
+            let pending = Arc::clone(&self.sync_pending);
+            let syncer = self.syncer.get_or_insert_with(|| {
+                let (tx, rx) = mpsc::channel();
+                thread::spawn(move || sync_periodically(rx, interval, pending));
+                tx
+            });
+            let _ = syncer.send(file);
+        }
+        Ok(())
+    }
 
-        for i in (1..=self.rotate_keep).rev() {
-            let from = self.rotated_path(i)?;
-            let to = self.rotated_path(i + 1)?;
-            if from.exists() {
-                fs::rename(from, to)?;
+    fn close_if_reopen_requested(&mut self) -> io::Result<()> {
+        let generation = self.reopen_handle.generation();
+        if generation != self.reopen_generation {
+            self.reopen_generation = generation;
+            if let Some(mut file) = self.file.take() {
+                file.flush()?;
             }
         }
-        if self.path.exists() {
-            let rotated_path = self.rotated_path(1)?;
-            #[cfg(feature = "libflate")]
-            {
-                if self.rotate_compress {
-                    let (plain_path, temp_gz_path) = self.rotated_paths_for_compression()?;
-                    let (tx, rx) = mpsc::channel();
+        Ok(())
+    }
+
+    /// Sets the template of the log file path, and evaluates it.
+    fn set_path_template(&mut self, template: String, timestamp_template: String) {
+        self.path_template = Some(template);
+        self.timestamp_template = timestamp_template;
+        if let Some(path) = self.evaluate_path_template() {
+            self.path = path;
+        }
+    }
 
-                    fs::rename(&self.path, &plain_path)?;
-                    thread::spawn(move || {
-                        let result = Self::compress(plain_path, temp_gz_path, rotated_path);
-                        let _ = tx.send(result);
-                    });
+    fn evaluate_path_template(&self) -> Option<PathBuf> {
+        let template = self.path_template.as_ref()?;
+        Some(path_template_to_path(
+            template,
+            &self.timestamp_template,
+            self.timezone,
+            Utc::now(),
+        ))
+    }
 
-                    self.wait_compression = Some(rx);
-                } else {
-                    fs::rename(&self.path, rotated_path)?;
+    fn update_symlink(&self) -> io::Result<()> {
+        #[cfg(unix)]
+        if let Some(ref symlink) = self.symlink {
+            // The link target is relative if possible, so that the directory can be moved.
+            // Otherwise, it is absolute, because a relative target is resolved against
+            // the directory of the link rather than the current directory.
+            let target = match self.path.file_name() {
+                Some(name) if symlink.parent() == self.path.parent() => PathBuf::from(name),
+                Some(name) => {
+                    let dir = match self.path.parent() {
+                        Some(dir) if !dir.as_os_str().is_empty() => dir,
+                        _ => Path::new("."),
+                    };
+                    fs::canonicalize(dir)?.join(name)
                 }
+                None => self.path.clone(),
+            };
+            if symlink == &self.path || fs::read_link(symlink).is_ok_and(|t| t == target) {
+                return Ok(());
             }
-            #[cfg(not(feature = "libflate"))]
-            fs::rename(&self.path, rotated_path)?;
-        }
 
-        let delete_path = self.rotated_path(self.rotate_keep + 1)?;
-        if delete_path.exists() {
-            fs::remove_file(delete_path)?;
+            // Replaces the link atomically.
+            let mut temp = symlink.clone().into_os_string();
+            temp.push(".temp");
+            let _ = fs::remove_file(&temp);
+            std::os::unix::fs::symlink(&target, &temp)?;
+            fs::rename(temp, symlink)?;
         }
-
-        self.written_size = 0;
-        self.next_reopen_check = Instant::now();
-        self.reopen_if_needed()?;
-
         Ok(())
     }
-    fn rotated_path(&self, i: usize) -> io::Result<PathBuf> {
-        let path = self.path.to_str().ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::InvalidInput,
-                format!("Non UTF-8 log file path: {:?}", self.path),
-            )
-        })?;
-        #[cfg(feature = "libflate")]
-        {
-            if self.rotate_compress {
-                Ok(PathBuf::from(format!("{}.{}.gz", path, i)))
-            } else {
-                Ok(PathBuf::from(format!("{}.{}", path, i)))
+
+    fn create_parent_dirs(&self) -> io::Result<()> {
+        match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() && !parent.exists() => {
+                let missing_dirs = parent
+                    .ancestors()
+                    .take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+                    .collect::<Vec<_>>();
+                let mut dir_builder = fs::DirBuilder::new();
+                dir_builder.recursive(true);
+                #[cfg(unix)]
+                if let Some(mode) = self.dir_mode {
+                    dir_builder.mode(mode);
+                }
+                dir_builder.create(parent)?;
+
+                // The mode given to `DirBuilder` is masked by the umask.
+                for dir in missing_dirs {
+                    set_permissions(dir, self.dir_mode)?;
+                }
+                Ok(())
             }
+            _ => Ok(()),
         }
-        #[cfg(not(feature = "libflate"))]
-        Ok(PathBuf::from(format!("{}.{}", path, i)))
-    }
-    #[cfg(feature = "libflate")]
-    fn rotated_paths_for_compression(&self) -> io::Result<(PathBuf, PathBuf)> {
-        let path = self.path.to_str().ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::InvalidInput,
-                format!("Non UTF-8 log file path: {:?}", self.path),
-            )
-        })?;
-        Ok((
-            PathBuf::from(format!("{}.1", path)),
-            PathBuf::from(format!("{}.1.gz.temp", path)),
-        ))
     }
-    #[cfg(feature = "libflate")]
-    fn compress(input_path: PathBuf, temp_path: PathBuf, output_path: PathBuf) -> io::Result<()> {
-        let mut input = File::open(&input_path)?;
-        let mut temp = GzipEncoder::new(File::create(&temp_path)?)?;
-        io::copy(&mut input, &mut temp)?;
-        temp.finish().into_result()?;
 
-        fs::rename(temp_path, output_path)?;
-        fs::remove_file(input_path)?;
+    fn rotate_if_interval_elapsed(&mut self) -> io::Result<()> {
+        if let Some(next_rotation) = self.next_rotation {
+            if Utc::now() >= next_rotation {
+                self.rotate()?;
+            }
+        }
         Ok(())
     }
-}
 
-impl Write for FileAppender {
+    fn rotate(&mut self) -> io::Result<()> {
+        if let Some(ref mut rx) = self.wait_compression {
+            use std::sync::mpsc::TryRecvError;
+            match rx.try_recv() {
+                Err(TryRecvError::Empty) => {
+                    // The previous compression is in progress
+                    return Ok(());
+                }
+                Err(TryRecvError::Disconnected) => {
+                    let e = io::Error::other("Log file compression thread aborted");
+                    return Err(e);
+                }
+                Ok(result) => {
+                    result?;
+                }
+            }
+        }
+        self.wait_compression = None;
+
+        let _ = self.file.take();
+
+        match self.evaluate_path_template() {
+            Some(path) if path != self.path => {
+                // The next file has a different name, so the current file is left as it is.
+                let rotated = mem::replace(&mut self.path, path);
+                self.template_paths.push(rotated.clone());
+                self.remove_expired_template_files(&rotated)?;
+                if rotated.exists() {
+                    self.compress_rotated(rotated)?;
+                }
+            }
+            _ => {
+                self.remove_expired_rotated_files()?;
+                match self.rotate_naming {
+                    RotateNaming::Sequential => self.rotate_sequential()?,
+                    RotateNaming::Timestamp => self.rotate_timestamp()?,
+                }
+            }
+        }
+
+        self.written_size = 0;
+        self.next_rotation = self
+            .rotate_interval
+            .next_boundary(self.timezone, Utc::now());
+        self.next_reopen_check = Instant::now();
+        self.reopen_if_needed()?;
+
+        Ok(())
+    }
+    fn rotate_sequential(&mut self) -> io::Result<()> {
+        for i in (1..=self.rotate_keep).rev() {
+            let from = self.rotated_path(i)?;
+            let to = self.rotated_path(i + 1)?;
+            if from.exists() {
+                fs::rename(from, to)?;
+            }
+        }
+        if self.path.exists() {
+            let path = self.path_str()?;
+            self.move_to_rotated(PathBuf::from(format!("{}.1", path)))?;
+        }
+
+        let delete_path = self.rotated_path(self.rotate_keep + 1)?;
+        if delete_path.exists() {
+            fs::remove_file(delete_path)?;
+        }
+        Ok(())
+    }
+    fn rotate_timestamp(&mut self) -> io::Result<()> {
+        // Make room for the file being rotated now.
+        let mut rotated_files = self.timestamped_rotated_files()?;
+        let delete_count = (rotated_files.len() + 1).saturating_sub(self.rotate_keep);
+        for (_, path) in rotated_files.drain(..delete_count.min(rotated_files.len())) {
+            fs::remove_file(path)?;
+        }
+
+        if self.path.exists() {
+            if self.rotate_keep == 0 {
+                fs::remove_file(&self.path)?;
+            } else {
+                let base = self.timestamped_rotated_base(&rotated_files)?;
+                self.move_to_rotated(base)?;
+            }
+        }
+        Ok(())
+    }
+    /// Deletes rotated files exceeding `rotate_max_age` or `rotate_max_total_size`.
+    ///
+    /// This must be called before the current log file is moved.
+    fn remove_expired_rotated_files(&self) -> io::Result<()> {
+        if self.rotate_max_age.is_none() && self.rotate_max_total_size == u64::MAX {
+            return Ok(());
+        }
+
+        let now = SystemTime::now();
+        let mut total_size = fs::metadata(&self.path).map_or(0, |m| m.len());
+        for path in self.rotated_files()?.iter().rev() {
+            let metadata = fs::metadata(path)?;
+            total_size = total_size.saturating_add(metadata.len());
+
+            let expired = match (self.rotate_max_age, metadata.modified()) {
+                (Some(max_age), Ok(modified)) => {
+                    now.duration_since(modified).is_ok_and(|age| age > max_age)
+                }
+                _ => false,
+            };
+            if expired || total_size > self.rotate_max_total_size {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+    /// Deletes the files of the path template exceeding `rotate_keep`, `rotate_max_age`
+    /// or `rotate_max_total_size`.
+    ///
+    /// The files of the template are those in the directory of `rotated` (the file being rotated
+    /// now) whose names match the file name template (see `template_file_base`).
+    /// Unless `rotate_cleanup_existing` is set, only the files of `template_paths` (i.e., written
+    /// by this appender) are considered. They are ordered by their last modification times.
+    /// `rotated` itself is only deleted if `rotate_keep` is `0`.
+    fn remove_expired_template_files(&mut self, rotated: &Path) -> io::Result<()> {
+        let format = match self
+            .path_template
+            .as_ref()
+            .and_then(|template| Path::new(template).file_name())
+            .and_then(|name| name.to_str())
+        {
+            Some(name) => path_template_to_format(name, &self.timestamp_template),
+            None => return Ok(()),
+        };
+        let dir = match rotated.parent() {
+            Some(dir) if dir != Path::new("") => dir,
+            _ => Path::new("."),
+        };
+        let is_written = |base: &str| {
+            self.template_paths.iter().any(|path| {
+                path.parent() == rotated.parent() && path.file_name() == Some(base.as_ref())
+            })
+        };
+
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let base = match name
+                .to_str()
+                .and_then(|name| template_file_base(name, &format, &self.rotate_timestamp_template))
+            {
+                Some(base) => base.to_owned(),
+                None => continue,
+            };
+            if Some(name.as_os_str()) == self.path.file_name() {
+                // The next log file (e.g., created by a previous process).
+                continue;
+            }
+            if !self.rotate_cleanup_existing && !is_written(&base) {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            if metadata.is_file() {
+                let is_rotated = Some(name.as_os_str()) == rotated.file_name();
+                files.push((
+                    metadata.modified()?,
+                    entry.path(),
+                    metadata.len(),
+                    is_rotated,
+                    base,
+                ));
+            }
+        }
+        files.sort();
+
+        let now = SystemTime::now();
+        let mut total_size = 0u64;
+        let mut kept_bases = Vec::new();
+        for (i, (modified, path, size, is_rotated, base)) in files.into_iter().rev().enumerate() {
+            total_size = total_size.saturating_add(size);
+            let expired = match self.rotate_max_age {
+                Some(max_age) => now.duration_since(modified).is_ok_and(|age| age > max_age),
+                None => false,
+            };
+            let delete = if is_rotated {
+                self.rotate_keep == 0
+            } else {
+                i >= self.rotate_keep || expired || total_size > self.rotate_max_total_size
+            };
+            if delete {
+                fs::remove_file(path)?;
+            } else {
+                kept_bases.push(base);
+            }
+        }
+
+        // Forgets the paths whose files are all deleted.
+        self.template_paths.retain(|path| {
+            path.parent() != rotated.parent()
+                || path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| kept_bases.iter().any(|base| base == name))
+        });
+        Ok(())
+    }
+    /// Returns the rotated log files, oldest first.
+    fn rotated_files(&self) -> io::Result<Vec<PathBuf>> {
+        match self.rotate_naming {
+            RotateNaming::Sequential => {
+                let mut files = Vec::new();
+                for i in (1..=self.rotate_keep).rev() {
+                    let path = self.rotated_path(i)?;
+                    if path.exists() {
+                        files.push(path);
+                    }
+                }
+                Ok(files)
+            }
+            RotateNaming::Timestamp => Ok(self
+                .timestamped_rotated_files()?
+                .into_iter()
+                .map(|(_, path)| path)
+                .collect()),
+        }
+    }
+    fn path_str(&self) -> io::Result<&str> {
+        self.path.to_str().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Non UTF-8 log file path: {:?}", self.path),
+            )
+        })
+    }
+    /// Returns the suffix appended to rotated files by compression (e.g., `".gz"`).
+    fn compression_suffix(&self) -> &'static str {
+        self.rotate_compression.suffix()
+    }
+    fn rotated_path(&self, i: usize) -> io::Result<PathBuf> {
+        let path = self.path_str()?;
+        Ok(PathBuf::from(format!(
+            "{}.{}{}",
+            path,
+            i,
+            self.compression_suffix()
+        )))
+    }
+    /// Moves the current log file to `base` (the rotated path without the compression suffix),
+    /// and then starts compressing it if needed.
+    fn move_to_rotated(&mut self, base: PathBuf) -> io::Result<()> {
+        fs::rename(&self.path, &base)?;
+        self.compress_rotated(base)
+    }
+
+    /// Compresses the rotated file `base` in the background, if compression is enabled.
+    fn compress_rotated(&mut self, base: PathBuf) -> io::Result<()> {
+        let compression = self.rotate_compression;
+        let file_mode = self.file_mode;
+        if compression == Compression::None {
+            return set_permissions(&base, file_mode);
+        }
+
+        let suffix = compression.suffix();
+        let temp_path = PathBuf::from(format!("{}{}.temp", base.display(), suffix));
+        let rotated_path = PathBuf::from(format!("{}{}", base.display(), suffix));
+        let level = self.rotate_compression_level;
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            let result =
+                Self::compress(compression, level, file_mode, base, temp_path, rotated_path);
+            let _ = tx.send(result);
+        });
+
+        self.wait_compression = Some(rx);
+        Ok(())
+    }
+
+    /// Returns the path for the file being rotated now, without the compression suffix.
+    fn timestamped_rotated_base(
+        &self,
+        rotated_files: &[((NaiveDateTime, u64), PathBuf)],
+    ) -> io::Result<PathBuf> {
+        let path = self.path_str()?;
+        let timestamp =
+            format_timestamp(&self.rotate_timestamp_template, self.timezone, Utc::now());
+
+        // If some files have been rotated within the same timestamp,
+        // a sequence number is appended so that the newest file is sorted last.
+        let parsed = parse_rotated_timestamp(&timestamp, &self.rotate_timestamp_template);
+        let mut i = rotated_files
+            .iter()
+            .filter(|((t, _), _)| Some(*t) == parsed.map(|(t, _)| t))
+            .map(|((_, i), _)| i + 1)
+            .max()
+            .unwrap_or(0);
+        loop {
+            let base = if i == 0 {
+                format!("{}.{}", path, timestamp)
+            } else {
+                format!("{}.{}.{}", path, timestamp, i)
+            };
+            if !Path::new(&base).exists()
+                && !Path::new(&format!("{}{}", base, self.compression_suffix())).exists()
+            {
+                return Ok(PathBuf::from(base));
+            }
+            i += 1;
+        }
+    }
+    /// Returns the rotated log files named by `RotateNaming::Timestamp`, oldest first.
+    fn timestamped_rotated_files(&self) -> io::Result<Vec<((NaiveDateTime, u64), PathBuf)>> {
+        let prefix = match self.path.file_name().and_then(|name| name.to_str()) {
+            Some(name) => format!("{}.", name),
+            None => return Ok(Vec::new()),
+        };
+        let dir = match self.path.parent() {
+            Some(dir) if dir != Path::new("") => dir,
+            _ => Path::new("."),
+        };
+
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let rest = match name.to_str().and_then(|name| name.strip_prefix(&prefix)) {
+                Some(rest) => rest,
+                None => continue,
+            };
+            if rest.ends_with(".temp") {
+                // Compression is in progress.
+                continue;
+            }
+            let rest = Compression::SUFFIXES
+                .iter()
+                .find_map(|suffix| rest.strip_suffix(suffix))
+                .unwrap_or(rest);
+            if let Some(key) = parse_rotated_timestamp(rest, &self.rotate_timestamp_template) {
+                files.push((key, entry.path()));
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+    fn compress(
+        compression: Compression,
+        level: Option<i32>,
+        file_mode: Option<u32>,
+        input_path: PathBuf,
+        temp_path: PathBuf,
+        output_path: PathBuf,
+    ) -> io::Result<()> {
+        let mut input = File::open(&input_path)?;
+        let mut temp_builder = OpenOptions::new();
+        temp_builder.write(true).create(true).truncate(true);
+        set_file_mode(&mut temp_builder, file_mode);
+        let temp = temp_builder.open(&temp_path)?;
+        compression.encode(level, &mut input, temp)?;
+
+        fs::rename(temp_path, &output_path)?;
+        // The mode given to `OpenOptions` is masked by the umask.
+        set_permissions(&output_path, file_mode)?;
+        fs::remove_file(input_path)?;
+        Ok(())
+    }
+}
+
+impl Write for FileAppender {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !self.in_record {
+            // Rotation only happens between records, so that a record is never split
+            // into two files.
+            self.in_record = true;
+            self.close_if_reopen_requested()?;
+            self.rotate_if_interval_elapsed()?;
+        }
         self.reopen_if_needed()?;
         let size = if let Some(ref mut f) = self.file {
             f.write(buf)?
         } else {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("Cannot open file: {:?}", self.path),
-            ));
+            return Err(io::Error::other(format!(
+                "Cannot open file: {:?}",
+                self.path
+            )));
         };
 
         self.written_size += size as u64;
         Ok(size)
     }
     fn flush(&mut self) -> io::Result<()> {
+        self.in_record = false;
+        let sync = self.is_sync_needed();
         if let Some(ref mut f) = self.file {
             f.flush()?;
+            if sync {
+                f.get_ref().sync_data()?;
+            }
         }
         if self.written_size >= self.rotate_size {
             self.rotate()?;
@@ -381,6 +1366,340 @@ impl Write for FileAppender {
     }
 }
 
+impl Drop for FileAppender {
+    fn drop(&mut self) {
+        // Stops the thread spawned by `FileLoggerBuilder::reopen_signal`,
+        // which unregisters the signal.
+        #[cfg(all(unix, feature = "signal-hook"))]
+        if let Some(ref signals) = self.signals {
+            signals.close();
+        }
+
+        // Syncs the records written after the last periodic sync.
+        if let (SyncPolicy::Interval(_), Some(f)) = (self.sync_policy, &mut self.file) {
+            if f.flush().is_ok() {
+                let _ = f.get_ref().sync_data();
+            }
+        }
+    }
+}
+
+/// Syncs the file received from `rx` every `interval`, if records have been written
+/// since the last sync (i.e., `pending` is set).
+///
+/// When a new file is received (e.g., after rotation), the old file is synced and replaced.
+/// The thread exits when the `FileAppender` is dropped.
+fn sync_periodically(rx: mpsc::Receiver<File>, interval: Duration, pending: Arc<AtomicBool>) {
+    let mut file: Option<File> = None;
+    loop {
+        match rx.recv_timeout(interval) {
+            Ok(new_file) => {
+                if let Some(old_file) = file.replace(new_file) {
+                    let _ = old_file.sync_data();
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                if pending.swap(false, Ordering::SeqCst) {
+                    if let Some(ref f) = file {
+                        let _ = f.sync_data();
+                    }
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+/// The wall-clock interval at which log files are rotated.
+///
+/// # Examples
+///
+/// The default value:
+///
+/// ```
+/// use sloggers::file::RotateInterval;
+///
+/// assert_eq!(RotateInterval::default(), RotateInterval::Never);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum RotateInterval {
+    /// Never rotate log files based on time.
+    #[default]
+    Never,
+
+    /// Rotate log files at the beginning of every hour.
+    Hourly,
+
+    /// Rotate log files at midnight.
+    Daily,
+
+    /// Rotate log files at midnight between Sunday and Monday.
+    Weekly,
+}
+impl RotateInterval {
+    /// Returns the first boundary of this interval after `date_time`.
+    fn next_boundary(self, timezone: TimeZone, date_time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match timezone {
+            TimeZone::Utc => self.next_boundary_in(&date_time),
+            TimeZone::Local => self.next_boundary_in(&date_time.with_timezone(&Local)),
+        }
+    }
+
+    fn next_boundary_in<Tz: ChronoTimeZone>(
+        self,
+        date_time: &DateTime<Tz>,
+    ) -> Option<DateTime<Utc>> {
+        let local = date_time.naive_local();
+        let date = local.date();
+        let boundary: NaiveDateTime = match self {
+            RotateInterval::Never => return None,
+            RotateInterval::Hourly => {
+                date.and_hms_opt(local.hour(), 0, 0)? + ChronoDuration::hours(1)
+            }
+            RotateInterval::Daily => date.and_hms_opt(0, 0, 0)? + ChronoDuration::days(1),
+            RotateInterval::Weekly => {
+                let days_to_monday = 7 - i64::from(date.weekday().num_days_from_monday());
+                date.and_hms_opt(0, 0, 0)? + ChronoDuration::days(days_to_monday)
+            }
+        };
+
+        // If the boundary falls into a gap caused by a DST transition,
+        // the first existing time after the gap is used instead.
+        let timezone = date_time.timezone();
+        timezone
+            .from_local_datetime(&boundary)
+            .earliest()
+            .or_else(|| {
+                timezone
+                    .from_local_datetime(&(boundary + ChronoDuration::hours(1)))
+                    .earliest()
+            })
+            .map(|t| t.with_timezone(&Utc))
+    }
+}
+impl FromStr for RotateInterval {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "never" => Ok(RotateInterval::Never),
+            "hourly" => Ok(RotateInterval::Hourly),
+            "daily" => Ok(RotateInterval::Daily),
+            "weekly" => Ok(RotateInterval::Weekly),
+            _ => track_panic!(ErrorKind::Invalid, "Undefined rotate interval: {:?}", s),
+        }
+    }
+}
+
+/// The algorithm used for compressing rotated log files.
+///
+/// # Examples
+///
+/// The default value:
+///
+/// ```
+/// use sloggers::file::Compression;
+///
+/// assert_eq!(Compression::default(), Compression::None);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum Compression {
+    /// Rotated files are not compressed.
+    #[default]
+    None,
+
+    /// GZIP (the suffix is ".gz").
+    #[cfg(feature = "libflate")]
+    Gzip,
+
+    /// Zstandard (the suffix is ".zst").
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+impl Compression {
+    /// The suffixes of all supported algorithms, regardless of enabled features.
+    const SUFFIXES: &'static [&'static str] = &[".gz", ".zst"];
+
+    fn suffix(self) -> &'static str {
+        match self {
+            Compression::None => "",
+            #[cfg(feature = "libflate")]
+            Compression::Gzip => ".gz",
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => ".zst",
+        }
+    }
+
+    #[allow(unused_variables)]
+    fn encode(self, level: Option<i32>, input: &mut File, output: File) -> io::Result<()> {
+        match self {
+            Compression::None => {
+                let mut output = output;
+                io::copy(input, &mut output)?;
+            }
+            #[cfg(feature = "libflate")]
+            Compression::Gzip => {
+                let mut encoder = if level == Some(0) {
+                    let options = GzipEncodeOptions::new().no_compression();
+                    GzipEncoder::with_options(output, options)?
+                } else {
+                    GzipEncoder::new(output)?
+                };
+                io::copy(input, &mut encoder)?;
+                encoder.finish().into_result()?;
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => {
+                zstd::stream::copy_encode(input, output, level.unwrap_or(0))?;
+            }
+        }
+        Ok(())
+    }
+}
+impl FromStr for Compression {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "none" => Ok(Compression::None),
+            #[cfg(feature = "libflate")]
+            "gzip" => Ok(Compression::Gzip),
+            #[cfg(feature = "zstd")]
+            "zstd" => Ok(Compression::Zstd),
+            _ => track_panic!(ErrorKind::Invalid, "Undefined compression: {:?}", s),
+        }
+    }
+}
+
+/// The naming scheme of rotated log files.
+///
+/// # Examples
+///
+/// The default value:
+///
+/// ```
+/// use sloggers::file::RotateNaming;
+///
+/// assert_eq!(RotateNaming::default(), RotateNaming::Sequential);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum RotateNaming {
+    /// Rotated files are named `"${ORIGINAL_FILE_NAME}.1"`, `"${ORIGINAL_FILE_NAME}.2"`, ...
+    /// (the larger the number, the older the file).
+    #[default]
+    Sequential,
+
+    /// Rotated files are named after their rotation time
+    /// (e.g., `"${ORIGINAL_FILE_NAME}.2018-09-18T10-19-51"`).
+    Timestamp,
+}
+impl FromStr for RotateNaming {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "sequential" => Ok(RotateNaming::Sequential),
+            "timestamp" => Ok(RotateNaming::Timestamp),
+            _ => track_panic!(ErrorKind::Invalid, "Undefined rotate naming: {:?}", s),
+        }
+    }
+}
+
+/// The policy of syncing log files to the storage device (i.e., calling `fsync`).
+///
+/// Syncing makes log records survive power loss or OS crashes, at the cost of performance.
+///
+/// In configuration files, the policy is written as one of the following strings:
+///
+/// - `"never"`
+/// - `"every_record"`
+/// - `"interval=DURATION"` (e.g., `"interval=1s"` or `"interval=500ms"`)
+/// - `"on_level=SEVERITY"` (e.g., `"on_level=error"`)
+///
+/// # Examples
+///
+/// ```
+/// use sloggers::file::SyncPolicy;
+/// use sloggers::types::Severity;
+/// use std::time::Duration;
+///
+/// assert_eq!(SyncPolicy::default(), SyncPolicy::Never);
+/// assert_eq!(
+///     "interval=1s".parse::<SyncPolicy>().unwrap(),
+///     SyncPolicy::Interval(Duration::from_secs(1))
+/// );
+/// assert_eq!(
+///     "on_level=error".parse::<SyncPolicy>().unwrap(),
+///     SyncPolicy::OnLevel(Severity::Error)
+/// );
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+#[non_exhaustive]
+pub enum SyncPolicy {
+    /// Log files are never synced explicitly (the OS decides when to write them back).
+    #[default]
+    Never,
+
+    /// Log files are synced after every log record.
+    EveryRecord,
+
+    /// Log files are synced by a background thread at the given interval,
+    /// if log records have been written since the last sync.
+    ///
+    /// The file is also synced when the logger is dropped
+    /// (or the [`AsyncGuard`](../struct.AsyncGuard.html) is flushed).
+    /// Note that the records written within the last interval before a crash or power loss
+    /// may still be lost, as well as the records queued in the asynchronous channel.
+    Interval(Duration),
+
+    /// Log files are synced after a log record whose level is at least the given one is written.
+    OnLevel(Severity),
+}
+impl FromStr for SyncPolicy {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(interval) = s.strip_prefix("interval=") {
+            let interval = if let Some(millis) = interval.strip_suffix("ms") {
+                millis.parse().map(Duration::from_millis)
+            } else if let Some(secs) = interval.strip_suffix('s') {
+                secs.parse().map(Duration::from_secs)
+            } else {
+                track_panic!(ErrorKind::Invalid, "Undefined sync interval unit: {:?}", s);
+            };
+            let interval = track!(interval.map_err(|e| Error::from(ErrorKind::Invalid.cause(e))))?;
+            return Ok(SyncPolicy::Interval(interval));
+        }
+        if let Some(level) = s.strip_prefix("on_level=") {
+            return Ok(SyncPolicy::OnLevel(track!(level.parse())?));
+        }
+        match s {
+            "never" => Ok(SyncPolicy::Never),
+            "every_record" => Ok(SyncPolicy::EveryRecord),
+            _ => track_panic!(ErrorKind::Invalid, "Undefined sync policy: {:?}", s),
+        }
+    }
+}
+impl TryFrom<String> for SyncPolicy {
+    type Error = Error;
+    fn try_from(s: String) -> Result<Self> {
+        track!(s.parse())
+    }
+}
+impl From<SyncPolicy> for String {
+    fn from(policy: SyncPolicy) -> String {
+        match policy {
+            SyncPolicy::Never => "never".to_owned(),
+            SyncPolicy::EveryRecord => "every_record".to_owned(),
+            SyncPolicy::Interval(interval) => format!("interval={}ms", interval.as_millis()),
+            SyncPolicy::OnLevel(level) => format!("on_level={}", level.as_str()),
+        }
+    }
+}
+
 /// The configuration of `FileLoggerBuilder`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -389,7 +1708,19 @@ pub struct FileLoggerConfig {
     #[serde(default)]
     pub level: Severity,
 
+    /// Per-module log level filter (e.g., `"info,my_crate::db=debug,hyper=warn"`).
+    ///
+    /// For details, see the documentation of [`ModuleFilter`].
+    ///
+    /// [`ModuleFilter`]: ../types/struct.ModuleFilter.html
+    #[serde(default)]
+    pub filter: Option<ModuleFilter>,
+
     /// Log record format.
+    ///
+    /// The possible values are `full`, `compact`, `json`, or `logfmt`.
+    ///
+    /// The default value is `full`.
     #[serde(default)]
     pub format: Format,
 
@@ -414,6 +1745,14 @@ pub struct FileLoggerConfig {
     ///
     /// All occurrences of the substring "{timestamp}" will be replaced with the current timestamp
     /// formatted according to `timestamp_template`. The timestamp will respect the `timezone` setting.
+    /// Likewise, "{date}" will be replaced with the current date.
+    /// The placeholders `{hostname}`, `{pid}`, `{exe}` and `${NAME}` are also replaced,
+    /// as in `context`.
+    ///
+    /// The template is evaluated again each time the log file is rotated.
+    /// For details, see the documentation of [`enable_path_template`].
+    ///
+    /// [`enable_path_template`]: ./struct.FileLoggerBuilder.html#method.enable_path_template
     pub path: PathBuf,
 
     /// Asynchronous channel size
@@ -432,6 +1771,40 @@ pub struct FileLoggerConfig {
     #[serde(default = "default_rotate_size")]
     pub rotate_size: u64,
 
+    /// Log file rotation interval.
+    ///
+    /// The possible values are `never`, `hourly`, `daily`, or `weekly`.
+    ///
+    /// For details, see the documentation of [`rotate_interval`].
+    ///
+    /// [`rotate_interval`]: ./struct.FileLoggerBuilder.html#method.rotate_interval
+    ///
+    /// The default value is `never`.
+    #[serde(default)]
+    pub rotate_interval: RotateInterval,
+
+    /// Naming scheme of rotated log files.
+    ///
+    /// The possible values are `sequential` or `timestamp`.
+    ///
+    /// For details, see the documentation of [`rotate_naming`].
+    ///
+    /// [`rotate_naming`]: ./struct.FileLoggerBuilder.html#method.rotate_naming
+    ///
+    /// The default value is `sequential`.
+    #[serde(default)]
+    pub rotate_naming: RotateNaming,
+
+    /// Format string for the rotation time in the names of rotated log files.
+    ///
+    /// For details, see the documentation of [`rotate_timestamp_template`].
+    ///
+    /// [`rotate_timestamp_template`]: ./struct.FileLoggerBuilder.html#method.rotate_timestamp_template
+    ///
+    /// Default: "%Y-%m-%dT%H-%M-%S", example: "2018-09-18T10-19-51"
+    #[serde(default = "default_rotate_timestamp_template")]
+    pub rotate_timestamp_template: String,
+
     /// Maximum number of rotated log files to keep.
     ///
     /// For details, see the documentation of [`rotate_keep`].
@@ -440,6 +1813,35 @@ pub struct FileLoggerConfig {
     #[serde(default = "default_rotate_keep")]
     pub rotate_keep: usize,
 
+    /// Maximum age of rotated log files to keep, in days.
+    ///
+    /// For details, see the documentation of [`rotate_max_age`].
+    ///
+    /// [`rotate_max_age`]: ./struct.FileLoggerBuilder.html#method.rotate_max_age
+    ///
+    /// By default, rotated log files are not deleted because of their age.
+    #[serde(default)]
+    pub rotate_max_age: Option<u64>,
+
+    /// Maximum total byte size of rotated log files to keep.
+    ///
+    /// For details, see the documentation of [`rotate_max_total_size`].
+    ///
+    /// [`rotate_max_total_size`]: ./struct.FileLoggerBuilder.html#method.rotate_max_total_size
+    #[serde(default = "default_rotate_max_total_size")]
+    pub rotate_max_total_size: u64,
+
+    /// Whether to apply the retention settings to the log files matching `path`
+    /// which were not written by this logger.
+    ///
+    /// For details, see the documentation of [`rotate_cleanup_existing`].
+    ///
+    /// [`rotate_cleanup_existing`]: ./struct.FileLoggerBuilder.html#method.rotate_cleanup_existing
+    ///
+    /// The default value is `false`.
+    #[serde(default)]
+    pub rotate_cleanup_existing: bool,
+
     /// Whether to compress or not compress rotated files.
     ///
     /// For details, see the documentation of [`rotate_compress`].
@@ -451,6 +1853,80 @@ pub struct FileLoggerConfig {
     #[cfg(feature = "libflate")]
     pub rotate_compress: bool,
 
+    /// Algorithm used for compressing rotated files.
+    ///
+    /// The possible values are `none`, `gzip` (if the `libflate` feature is enabled),
+    /// or `zstd` (if the `zstd` feature is enabled).
+    /// If this is `none` and `rotate_compress` is `true`, `gzip` is used.
+    ///
+    /// For details, see the documentation of [`rotate_compression`].
+    ///
+    /// [`rotate_compression`]: ./struct.FileLoggerBuilder.html#method.rotate_compression
+    ///
+    /// The default value is `none`.
+    #[serde(default)]
+    pub rotate_compression: Compression,
+
+    /// Compression level used for compressing rotated files.
+    ///
+    /// For details, see the documentation of [`rotate_compression_level`].
+    ///
+    /// [`rotate_compression_level`]: ./struct.FileLoggerBuilder.html#method.rotate_compression_level
+    ///
+    /// By default, the default level of each algorithm is used.
+    #[serde(default)]
+    pub rotate_compression_level: Option<i32>,
+
+    /// Permission bits of log files (e.g., `0o640`).
+    ///
+    /// For details, see the documentation of [`file_mode`].
+    ///
+    /// [`file_mode`]: ./struct.FileLoggerBuilder.html#method.file_mode
+    #[serde(default)]
+    #[cfg(unix)]
+    pub file_mode: Option<u32>,
+
+    /// Whether to create the missing parent directories of the log file.
+    ///
+    /// The default value is `false`.
+    #[serde(default)]
+    pub create_dirs: bool,
+
+    /// Permission bits of the parent directories created by `create_dirs` (e.g., `0o750`).
+    #[serde(default)]
+    #[cfg(unix)]
+    pub dir_mode: Option<u32>,
+
+    /// Path of the symbolic link which points to the current log file.
+    ///
+    /// For details, see the documentation of [`symlink`].
+    ///
+    /// [`symlink`]: ./struct.FileLoggerBuilder.html#method.symlink
+    #[serde(default)]
+    #[cfg(unix)]
+    pub symlink: Option<PathBuf>,
+
+    /// Policy of syncing log files to the storage device.
+    ///
+    /// The possible values are `never`, `every_record`, `interval=DURATION` (e.g., `interval=1s`),
+    /// or `on_level=SEVERITY` (e.g., `on_level=error`).
+    ///
+    /// The default value is `never`.
+    #[serde(default)]
+    pub sync_policy: SyncPolicy,
+
+    /// Signal which makes the logger reopen its log file.
+    ///
+    /// The possible values are `SIGHUP`, `SIGUSR1`, or `SIGUSR2`.
+    /// This is only available if the `signal-hook` feature is enabled.
+    ///
+    /// For details, see the documentation of [`reopen_signal`].
+    ///
+    /// [`reopen_signal`]: ./struct.FileLoggerBuilder.html#method.reopen_signal
+    #[serde(default)]
+    #[cfg(all(unix, feature = "signal-hook"))]
+    pub reopen_signal: Option<String>,
+
     /// Whether to drop logs on overflow.
     ///
     /// The possible values are `drop`, `drop_and_report`, or `block`.
@@ -458,6 +1934,23 @@ pub struct FileLoggerConfig {
     /// The default value is `drop_and_report`.
     #[serde(default)]
     pub overflow_strategy: OverflowStrategy,
+
+    /// Key-value filter (e.g., `{ severity = "warning", always_suppress_on_regex = "^debug" }`).
+    ///
+    /// For details, see the documentation of [`KVFilterParameters`].
+    ///
+    /// [`KVFilterParameters`]: ../types/struct.KVFilterParameters.html
+    #[serde(default)]
+    #[cfg(feature = "slog-kvfilter")]
+    pub kvfilter: Option<KVFilterParameters>,
+
+    /// Static key-value pairs attached to all log records (e.g., `{ service = "foo" }`).
+    ///
+    /// The values can contain the following placeholders:
+    /// `{hostname}`, `{pid}`, `{exe}` (the file name of the executable), and
+    /// `${NAME}` (the value of the environment variable `NAME`).
+    #[serde(default)]
+    pub context: BTreeMap<String, String>,
 }
 
 impl FileLoggerConfig {
@@ -470,24 +1963,63 @@ impl FileLoggerConfig {
 impl Config for FileLoggerConfig {
     type Builder = FileLoggerBuilder;
     fn try_to_builder(&self) -> Result<Self::Builder> {
-        let now = Utc::now();
-        let path_template = self.path.to_str().ok_or(ErrorKind::Invalid)?;
-        let path =
-            path_template_to_path(path_template, &self.timestamp_template, self.timezone, now);
-        let mut builder = FileLoggerBuilder::new(&path);
+        let mut builder = FileLoggerBuilder::new(&self.path);
+        builder.enable_path_template(&self.timestamp_template);
         builder.level(self.level);
+        if let Some(ref filter) = self.filter {
+            builder.filter(filter.clone());
+        }
         builder.format(self.format);
         builder.source_location(self.source_location);
         builder.timezone(self.timezone);
         builder.overflow_strategy(self.overflow_strategy);
         builder.channel_size(self.channel_size);
         builder.rotate_size(self.rotate_size);
+        builder.rotate_interval(self.rotate_interval);
+        builder.rotate_naming(self.rotate_naming);
+        builder.rotate_timestamp_template(&self.rotate_timestamp_template);
         builder.rotate_keep(self.rotate_keep);
+        if let Some(days) = self.rotate_max_age {
+            builder.rotate_max_age(Duration::from_secs(days.saturating_mul(24 * 60 * 60)));
+        }
+        builder.rotate_max_total_size(self.rotate_max_total_size);
+        builder.rotate_cleanup_existing(self.rotate_cleanup_existing);
+        builder.sync_policy(self.sync_policy);
+        #[cfg(all(unix, feature = "signal-hook"))]
+        if let Some(ref signal) = self.reopen_signal {
+            builder.reopen_signal(track!(signal_from_name(signal))?);
+        }
+        #[cfg(unix)]
+        if let Some(mode) = self.file_mode {
+            builder.file_mode(mode);
+        }
+        builder.create_dirs(self.create_dirs);
+        #[cfg(unix)]
+        if let Some(ref symlink) = self.symlink {
+            builder.symlink(symlink);
+        }
+        #[cfg(unix)]
+        if let Some(mode) = self.dir_mode {
+            builder.dir_mode(mode);
+        }
         #[cfg(feature = "libflate")]
         builder.rotate_compress(self.rotate_compress);
+        if self.rotate_compression != Compression::None {
+            builder.rotate_compression(self.rotate_compression);
+        }
+        if let Some(level) = self.rotate_compression_level {
+            builder.rotate_compression_level(level);
+        }
         if self.truncate {
             builder.truncate();
         }
+        #[cfg(feature = "slog-kvfilter")]
+        if let Some(ref parameters) = self.kvfilter {
+            builder.kvfilter(parameters.clone());
+        }
+        for (key, value) in &self.context {
+            builder.context(key, &track!(misc::expand_placeholders(value))?);
+        }
         Ok(builder)
     }
 }
@@ -496,6 +2028,7 @@ impl Default for FileLoggerConfig {
     fn default() -> Self {
         FileLoggerConfig {
             level: Severity::default(),
+            filter: None,
             format: Format::default(),
             source_location: SourceLocation::default(),
             overflow_strategy: OverflowStrategy::default(),
@@ -505,29 +2038,159 @@ impl Default for FileLoggerConfig {
             channel_size: default_channel_size(),
             truncate: false,
             rotate_size: default_rotate_size(),
+            rotate_interval: RotateInterval::default(),
+            rotate_naming: RotateNaming::default(),
+            rotate_timestamp_template: default_rotate_timestamp_template(),
             rotate_keep: default_rotate_keep(),
+            rotate_max_age: None,
+            rotate_max_total_size: default_rotate_max_total_size(),
+            rotate_cleanup_existing: false,
             #[cfg(feature = "libflate")]
             rotate_compress: false,
+            rotate_compression: Compression::default(),
+            rotate_compression_level: None,
+            sync_policy: SyncPolicy::default(),
+            #[cfg(all(unix, feature = "signal-hook"))]
+            reopen_signal: None,
+            #[cfg(unix)]
+            file_mode: None,
+            create_dirs: false,
+            #[cfg(unix)]
+            dir_mode: None,
+            #[cfg(unix)]
+            symlink: None,
+            #[cfg(feature = "slog-kvfilter")]
+            kvfilter: None,
+            context: BTreeMap::new(),
         }
     }
 }
 
-fn path_template_to_path(
-    path_template: &str,
+/// Converts a signal name accepted by `FileLoggerConfig::reopen_signal` to its number.
+#[cfg(all(unix, feature = "signal-hook"))]
+fn signal_from_name(name: &str) -> Result<i32> {
+    match name {
+        "SIGHUP" => Ok(libc::SIGHUP),
+        "SIGUSR1" => Ok(libc::SIGUSR1),
+        "SIGUSR2" => Ok(libc::SIGUSR2),
+        _ => track_panic!(ErrorKind::Invalid, "Unsupported reopen signal: {:?}", name),
+    }
+}
+
+/// Sets the permission bits of files created with `options` (only on Unix).
+fn set_file_mode(options: &mut OpenOptions, mode: Option<u32>) {
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        options.mode(mode);
+    }
+    #[cfg(not(unix))]
+    let _ = (options, mode);
+}
+
+/// Sets the permission bits of the existing file at `path` (only on Unix).
+fn set_permissions(path: &Path, mode: Option<u32>) -> io::Result<()> {
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+    }
+    #[cfg(not(unix))]
+    let _ = (path, mode);
+    Ok(())
+}
+
+fn path_template_to_path(
+    path_template: &str,
+    timestamp_template: &str,
+    timezone: TimeZone,
+    date_time: DateTime<Utc>,
+) -> PathBuf {
+    let mut path_string = path_template.to_owned();
+    if path_string.contains("{timestamp}") {
+        let timestamp_string = format_timestamp(timestamp_template, timezone, date_time);
+        path_string = path_string.replace("{timestamp}", &timestamp_string);
+    }
+    if path_string.contains("{date}") {
+        let date_string = format_timestamp("%Y%m%d", timezone, date_time);
+        path_string = path_string.replace("{date}", &date_string);
+    }
+    PathBuf::from(path_string)
+}
+
+/// Converts the file name part of a path template to a format string for parsing file names.
+fn path_template_to_format(name_template: &str, timestamp_template: &str) -> String {
+    name_template
+        .replace('%', "%%")
+        .replace("{timestamp}", timestamp_template)
+        .replace("{date}", "%Y%m%d")
+}
+
+/// Returns the file name part of the log file from which the file `name` originates,
+/// if `name` is a file of the path template.
+///
+/// `name` must match `format` (see `path_template_to_format`), optionally followed by
+/// a rotation suffix (`.${N}` or `.${TIMESTAMP}[.${N}]`) and a compression suffix (e.g., `.gz`).
+fn template_file_base<'a>(
+    name: &'a str,
+    format: &str,
+    rotate_timestamp_template: &str,
+) -> Option<&'a str> {
+    let matches = |s: &str| {
+        let mut parsed = chrono::format::Parsed::new();
+        chrono::format::parse(&mut parsed, s, chrono::format::StrftimeItems::new(format)).is_ok()
+    };
+    let name = Compression::SUFFIXES
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .unwrap_or(name);
+    if matches(name) {
+        return Some(name);
+    }
+    name.match_indices('.').find_map(|(i, _)| {
+        let (base, suffix) = (&name[..i], &name[i + 1..]);
+        let is_rotated = (!suffix.is_empty() && suffix.bytes().all(|b| b.is_ascii_digit()))
+            || parse_rotated_timestamp(suffix, rotate_timestamp_template).is_some();
+        if is_rotated && matches(base) {
+            Some(base)
+        } else {
+            None
+        }
+    })
+}
+
+fn format_timestamp(
     timestamp_template: &str,
     timezone: TimeZone,
     date_time: DateTime<Utc>,
-) -> PathBuf {
-    let timestamp_string = match timezone {
+) -> String {
+    match timezone {
         TimeZone::Local => {
             let local_timestamp = Local.from_utc_datetime(&date_time.naive_utc());
-            local_timestamp.format(&timestamp_template)
+            local_timestamp.format(timestamp_template)
         }
-        TimeZone::Utc => date_time.format(&timestamp_template),
+        TimeZone::Utc => date_time.format(timestamp_template),
     }
-    .to_string();
-    let path_string = path_template.replace("{timestamp}", &timestamp_string);
-    PathBuf::from(path_string)
+    .to_string()
+}
+
+/// Parses the part of a rotated file name which follows the original file name.
+///
+/// The part is either `"${TIMESTAMP}"` or `"${TIMESTAMP}.${N}"`
+/// (the latter is used if more than one file is rotated within the same timestamp).
+fn parse_rotated_timestamp(s: &str, timestamp_template: &str) -> Option<(NaiveDateTime, u64)> {
+    let parse = |s: &str| {
+        NaiveDateTime::parse_from_str(s, timestamp_template)
+            .ok()
+            .or_else(|| {
+                NaiveDate::parse_from_str(s, timestamp_template)
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+            })
+    };
+    if let Some(timestamp) = parse(s) {
+        return Some((timestamp, 0));
+    }
+    let (timestamp, i) = s.rsplit_once('.')?;
+    Some((parse(timestamp)?, i.parse().ok()?))
 }
 
 fn default_channel_size() -> usize {
@@ -535,8 +2198,10 @@ fn default_channel_size() -> usize {
 }
 
 fn default_rotate_size() -> u64 {
-    use std::u64;
+    u64::MAX
+}
 
+fn default_rotate_max_total_size() -> u64 {
     u64::MAX
 }
 
@@ -548,12 +2213,16 @@ fn default_timestamp_template() -> String {
     "%Y%m%d_%H%M".to_owned()
 }
 
+fn default_rotate_timestamp_template() -> String {
+    "%Y-%m-%dT%H-%M-%S".to_owned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{Build, ErrorKind};
-    use chrono::NaiveDateTime;
     use std::fs;
+    use std::process;
     use std::thread;
     use std::time::Duration;
     use tempfile::{Builder as TempDirBuilder, TempDir};
@@ -618,6 +2287,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "libflate")]
     fn file_gzip_rotation_works() {
         let dir = tempdir();
         let logger = FileLoggerBuilder::new(dir.path().join("foo.log"))
@@ -653,6 +2323,590 @@ mod tests {
         assert!(!dir.path().join("foo.log.3.gz").exists());
     }
 
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn file_zstd_rotation_works() {
+        let dir = tempdir();
+        let mut appender = FileAppender::new(dir.path().join("foo.log"));
+        appender.rotate_compression = Compression::Zstd;
+        appender.rotate_compression_level = Some(19);
+
+        appender.write_all(b"hello\n").unwrap();
+        appender.flush().unwrap();
+        appender.rotate().unwrap();
+        let rx = appender.wait_compression.take().unwrap();
+        rx.recv().unwrap().unwrap();
+
+        assert!(!dir.path().join("foo.log.1").exists());
+        assert!(!dir.path().join("foo.log.1.zst.temp").exists());
+        let compressed = fs::read(dir.path().join("foo.log.1.zst")).unwrap();
+        assert_eq!(zstd::decode_all(&compressed[..]).unwrap(), b"hello\n");
+    }
+
+    #[test]
+    fn file_interval_rotation_works() {
+        let dir = tempdir();
+        let mut appender = FileAppender::new(dir.path().join("foo.log"));
+        appender.rotate_interval = RotateInterval::Daily;
+
+        appender.write_all(b"hello\n").unwrap();
+        appender.flush().unwrap();
+        assert!(!dir.path().join("foo.log.1").exists());
+        assert!(appender.next_rotation.unwrap() > Utc::now());
+
+        // Pretend that midnight has passed.
+        appender.next_rotation = Some(Utc::now() - ChronoDuration::seconds(1));
+        appender.write_all(b"world\n").unwrap();
+        appender.flush().unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("foo.log.1")).unwrap(),
+            "hello\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("foo.log")).unwrap(),
+            "world\n"
+        );
+        assert!(appender.next_rotation.unwrap() > Utc::now());
+    }
+
+    #[test]
+    fn file_timestamp_rotation_works() {
+        let dir = tempdir();
+        let mut appender = FileAppender::new(dir.path().join("foo.log"));
+        appender.rotate_naming = RotateNaming::Timestamp;
+        appender.rotate_keep = 2;
+
+        for content in &["a", "b", "c", "d"] {
+            appender.write_all(content.as_bytes()).unwrap();
+            appender.flush().unwrap();
+            appender.rotate().unwrap();
+        }
+
+        let rotated = appender.timestamped_rotated_files().unwrap();
+        let contents = rotated
+            .iter()
+            .map(|(_, path)| fs::read_to_string(path).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(contents, ["c", "d"]);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn file_rotation_max_total_size_works() {
+        let dir = tempdir();
+        let mut appender = FileAppender::new(dir.path().join("foo.log"));
+        appender.rotate_max_total_size = 25;
+
+        for content in &["aaaaaaaaaa", "bbbbbbbbbb", "cccccccccc", "dddddddddd"] {
+            appender.write_all(content.as_bytes()).unwrap();
+            appender.flush().unwrap();
+            appender.rotate().unwrap();
+        }
+
+        let contents = appender
+            .rotated_files()
+            .unwrap()
+            .iter()
+            .map(|path| fs::read_to_string(path).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(contents, ["cccccccccc", "dddddddddd"]);
+    }
+
+    #[test]
+    fn file_rotation_max_age_works() {
+        let dir = tempdir();
+        let mut appender = FileAppender::new(dir.path().join("foo.log"));
+        appender.rotate_max_age = Some(Duration::from_secs(60 * 60));
+
+        appender.write_all(b"old").unwrap();
+        appender.flush().unwrap();
+        appender.rotate().unwrap();
+        appender.write_all(b"new").unwrap();
+        appender.flush().unwrap();
+        appender.rotate().unwrap();
+        assert!(dir.path().join("foo.log.2").exists());
+
+        let two_hours_ago = SystemTime::now() - Duration::from_secs(2 * 60 * 60);
+        File::options()
+            .write(true)
+            .open(dir.path().join("foo.log.2"))
+            .unwrap()
+            .set_modified(two_hours_ago)
+            .unwrap();
+
+        appender.write_all(b"newer").unwrap();
+        appender.flush().unwrap();
+        appender.rotate().unwrap();
+        assert!(!dir.path().join("foo.log.3").exists());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("foo.log.2")).unwrap(),
+            "new"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("foo.log.1")).unwrap(),
+            "newer"
+        );
+    }
+
+    #[test]
+    fn test_parse_rotated_timestamp() {
+        let template = "%Y-%m-%dT%H-%M-%S";
+        let time = |s| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(
+            parse_rotated_timestamp("2018-09-18T10-19-51", template),
+            Some((time("2018-09-18 10:19:51"), 0))
+        );
+        assert_eq!(
+            parse_rotated_timestamp("2018-09-18T10-19-51.3", template),
+            Some((time("2018-09-18 10:19:51"), 3))
+        );
+        assert_eq!(
+            parse_rotated_timestamp("20180918", "%Y%m%d"),
+            Some((time("2018-09-18 00:00:00"), 0))
+        );
+        assert_eq!(parse_rotated_timestamp("1", template), None);
+        assert_eq!(
+            parse_rotated_timestamp("2018-09-18T10-19-51.gz.temp", template),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rotate_interval_next_boundary() {
+        // 2018-09-18T10:19:51Z (Tuesday)
+        let now = Utc.timestamp_opt(1537265991, 0).unwrap();
+        let boundary = |interval: RotateInterval| {
+            interval
+                .next_boundary(TimeZone::Utc, now)
+                .map(|t| t.to_rfc3339())
+        };
+        assert_eq!(boundary(RotateInterval::Never), None);
+        assert_eq!(
+            boundary(RotateInterval::Hourly).unwrap(),
+            "2018-09-18T11:00:00+00:00"
+        );
+        assert_eq!(
+            boundary(RotateInterval::Daily).unwrap(),
+            "2018-09-19T00:00:00+00:00"
+        );
+        assert_eq!(
+            boundary(RotateInterval::Weekly).unwrap(),
+            "2018-09-24T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn json_format_works() {
+        let dir = tempdir();
+        let log_path = dir.path().join("foo.log");
+        let logger = FileLoggerBuilder::new(&log_path)
+            .format(Format::Json)
+            .timezone(TimeZone::Utc)
+            .build()
+            .unwrap();
+
+        info!(logger, "hello"; "key" => "value");
+        thread::sleep(Duration::from_millis(50));
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert_eq!(content.lines().count(), 1);
+        assert!(content.starts_with('{'));
+        assert!(content.contains(r#""ts":""#));
+        assert!(content.contains(r#""level":"INFO""#));
+        assert!(content.contains(r#""msg":"hello""#));
+        assert!(content.contains(r#""key":"value""#));
+        assert!(content.contains(r#""module":"sloggers::file::tests:"#));
+    }
+
+    #[test]
+    fn level_handle_works() {
+        let dir = tempdir();
+        let log_path = dir.path().join("foo.log");
+        let handle = LevelHandle::new(Severity::Info);
+        let logger = FileLoggerBuilder::new(&log_path)
+            .level_handle(handle.clone())
+            .build()
+            .unwrap();
+
+        debug!(logger, "foo");
+        handle.set(Severity::Debug);
+        debug!(logger, "bar");
+        thread::sleep(Duration::from_millis(50));
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert!(!content.contains("foo"));
+        assert!(content.contains("bar"));
+    }
+
+    #[test]
+    fn build_with_guard_works() {
+        let dir = tempdir();
+        let log_path = dir.path().join("foo.log");
+        let (logger, guard) = FileLoggerBuilder::new(&log_path)
+            .build_with_guard()
+            .unwrap();
+
+        for i in 0..100 {
+            info!(logger, "hello"; "i" => i);
+        }
+        guard.flush();
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert_eq!(content.lines().count(), 100);
+
+        // Records issued after the guard is dropped are discarded.
+        info!(logger, "hello");
+    }
+
+    #[test]
+    #[cfg(feature = "slog-kvfilter")]
+    fn kvfilter_config_works() {
+        let dir = tempdir();
+        let log_path = dir.path().join("foo.log");
+        let config: crate::LoggerConfig = serdeconv::from_toml_str(&format!(
+            r#"
+type = "file"
+path = {:?}
+
+[kvfilter]
+severity = "error"
+always_suppress_on_regex = "^noisy"
+"#,
+            log_path
+        ))
+        .unwrap();
+        let logger = config.build_logger().unwrap();
+
+        info!(logger, "noisy message");
+        info!(logger, "quiet message");
+        thread::sleep(Duration::from_millis(50));
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert!(!content.contains("noisy message"));
+        assert!(content.contains("quiet message"));
+    }
+
+    #[test]
+    fn context_works() {
+        let dir = tempdir();
+        let log_path = dir.path().join("foo.log");
+        let logger = FileLoggerBuilder::new(&log_path)
+            .context("service", "bar")
+            .build()
+            .unwrap();
+
+        info!(logger, "hello");
+        thread::sleep(Duration::from_millis(50));
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert!(content.contains("service: bar"));
+    }
+
+    #[test]
+    fn reopen_handle_works() {
+        let dir = tempdir();
+        let log_path = dir.path().join("foo.log");
+        let moved_path = dir.path().join("foo.log.1");
+        let handle = ReopenHandle::new();
+        let logger = FileLoggerBuilder::new(&log_path)
+            .reopen_handle(handle.clone())
+            .build()
+            .unwrap();
+
+        info!(logger, "foo");
+        thread::sleep(Duration::from_millis(50));
+
+        // Emulates `logrotate`, which moves the file and creates a new one immediately.
+        fs::rename(&log_path, &moved_path).unwrap();
+        File::create(&log_path).unwrap();
+        handle.reopen();
+
+        info!(logger, "bar");
+        thread::sleep(Duration::from_millis(50));
+
+        let moved = fs::read_to_string(&moved_path).unwrap();
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert!(moved.contains("foo"));
+        assert!(!moved.contains("bar"));
+        assert!(content.contains("bar"));
+    }
+
+    #[test]
+    #[cfg(all(unix, feature = "signal-hook"))]
+    fn reopen_on_signal_works() {
+        let dir = tempdir();
+        let log_path = dir.path().join("foo.log");
+        let moved_path = dir.path().join("foo.log.1");
+        let handle = ReopenHandle::new();
+        handle.reopen_on_signal(libc::SIGUSR2).unwrap();
+        let logger = FileLoggerBuilder::new(&log_path)
+            .reopen_handle(handle)
+            .build()
+            .unwrap();
+
+        info!(logger, "foo");
+        thread::sleep(Duration::from_millis(50));
+
+        fs::rename(&log_path, &moved_path).unwrap();
+        unsafe {
+            libc::raise(libc::SIGUSR2);
+        }
+        thread::sleep(Duration::from_millis(50));
+
+        info!(logger, "bar");
+        thread::sleep(Duration::from_millis(50));
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert!(content.contains("bar"));
+        assert!(!content.contains("foo"));
+    }
+
+    #[test]
+    #[cfg(all(unix, feature = "signal-hook"))]
+    fn reopen_signal_config_works() {
+        let dir = tempdir();
+        let log_path = dir.path().join("foo.log");
+        let moved_path = dir.path().join("foo.log.1");
+        let config: FileLoggerConfig = serdeconv::from_toml_str(&format!(
+            r#"
+path = {:?}
+reopen_signal = "SIGUSR1"
+"#,
+            log_path
+        ))
+        .unwrap();
+        let logger = config.build_logger().unwrap();
+
+        info!(logger, "foo");
+        thread::sleep(Duration::from_millis(50));
+
+        fs::rename(&log_path, &moved_path).unwrap();
+        unsafe {
+            libc::raise(libc::SIGUSR1);
+        }
+        thread::sleep(Duration::from_millis(50));
+
+        info!(logger, "bar");
+        thread::sleep(Duration::from_millis(50));
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert!(content.contains("bar"));
+        assert!(!content.contains("foo"));
+
+        assert!(signal_from_name("SIGKILL").is_err());
+    }
+
+    #[test]
+    #[cfg(all(unix, feature = "signal-hook"))]
+    fn reopen_signal_stops_on_drop() {
+        let dir = tempdir();
+        let mut appender = FileAppender::new(dir.path().join("foo.log"));
+        let signals = appender
+            .reopen_handle
+            .spawn_signal_thread(libc::SIGUSR1)
+            .unwrap();
+        appender.signals = Some(signals.clone());
+        assert!(!signals.is_closed());
+
+        drop(appender);
+        assert!(signals.is_closed());
+    }
+
+    #[test]
+    fn test_sync_policy_from_str() {
+        for (s, policy) in [
+            ("never", SyncPolicy::Never),
+            ("every_record", SyncPolicy::EveryRecord),
+            (
+                "interval=500ms",
+                SyncPolicy::Interval(Duration::from_millis(500)),
+            ),
+            ("on_level=error", SyncPolicy::OnLevel(Severity::Error)),
+        ] {
+            assert_eq!(s.parse::<SyncPolicy>().unwrap(), policy);
+            assert_eq!(String::from(policy), s);
+        }
+        assert_eq!(
+            "interval=2s".parse::<SyncPolicy>().unwrap(),
+            SyncPolicy::Interval(Duration::from_secs(2))
+        );
+        assert!("interval=2".parse::<SyncPolicy>().is_err());
+        assert!("on_level=foo".parse::<SyncPolicy>().is_err());
+    }
+
+    #[test]
+    fn sync_interval_works() {
+        let dir = tempdir();
+        let mut appender = FileAppender::new(dir.path().join("foo.log"));
+        appender.sync_policy = SyncPolicy::Interval(Duration::from_millis(10));
+
+        appender.write_all(b"hello\n").unwrap();
+        appender.flush().unwrap();
+        assert!(appender.sync_pending.load(Ordering::SeqCst));
+
+        // The record is synced without writing any more records.
+        thread::sleep(Duration::from_millis(200));
+        assert!(!appender.sync_pending.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn sync_on_level_works() {
+        let dir = tempdir();
+        let log_path = dir.path().join("foo.log");
+        let mut appender = FileAppender::new(&log_path);
+        appender.sync_policy = SyncPolicy::OnLevel(Severity::Error);
+        let requested = Arc::clone(&appender.sync_requested);
+        let drain = SyncOnLevel::new(
+            LogfmtDrain::new(appender, TimeZone::Utc),
+            Some(Level::Error),
+            Arc::clone(&requested),
+        );
+
+        drain
+            .log(
+                &record!(Level::Info, "", &format_args!("foo"), b!()),
+                &o!().into(),
+            )
+            .unwrap();
+        assert!(!requested.load(Ordering::SeqCst));
+
+        // The request is set before writing, and consumed by `FileAppender::flush`.
+        drain
+            .log(
+                &record!(Level::Error, "", &format_args!("bar"), b!()),
+                &o!().into(),
+            )
+            .unwrap();
+        assert!(!requested.load(Ordering::SeqCst));
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert!(content.contains("foo"));
+        assert!(content.contains("bar"));
+    }
+
+    #[test]
+    #[cfg(all(unix, feature = "libflate"))]
+    fn file_mode_works() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        let dir = tempdir();
+        let log_dir = dir.path().join("foo").join("bar");
+        let log_path = log_dir.join("foo.log");
+        let mut appender = FileAppender::new(&log_path);
+        appender.create_dirs = true;
+        // These modes are not masked by the umask.
+        appender.dir_mode = Some(0o770);
+        appender.file_mode = Some(0o660);
+        appender.rotate_compression = Compression::Gzip;
+
+        appender.write_all(b"hello\n").unwrap();
+        appender.flush().unwrap();
+        appender.rotate().unwrap();
+        let rx = appender.wait_compression.take().unwrap();
+        rx.recv().unwrap().unwrap();
+
+        assert_eq!(mode(&log_dir), 0o770);
+        assert_eq!(mode(&dir.path().join("foo")), 0o770);
+        assert_eq!(mode(&log_path), 0o660);
+        assert_eq!(mode(&log_dir.join("foo.log.1.gz")), 0o660);
+
+        // The mode of an existing file is fixed when it is opened.
+        let existing_path = dir.path().join("existing.log");
+        File::create(&existing_path).unwrap();
+        fs::set_permissions(&existing_path, fs::Permissions::from_mode(0o644)).unwrap();
+        let mut appender = FileAppender::new(&existing_path);
+        appender.file_mode = Some(0o600);
+        appender.write_all(b"hello\n").unwrap();
+        assert_eq!(mode(&existing_path), 0o600);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn symlink_works() {
+        let dir = tempdir();
+        let symlink = dir.path().join("current.log");
+        for name in &["foo_1.log", "foo_2.log"] {
+            let logger = FileLoggerBuilder::new(dir.path().join(name))
+                .symlink(&symlink)
+                .build()
+                .unwrap();
+            info!(logger, "hello"; "name" => name);
+            thread::sleep(Duration::from_millis(50));
+
+            assert_eq!(fs::read_link(&symlink).unwrap(), Path::new(name));
+            let content = fs::read_to_string(&symlink).unwrap();
+            assert!(content.contains(name));
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn symlink_in_other_dir_works() {
+        let dir = tempdir();
+        // The log file path is relative to the current directory
+        // (e.g., `../../tmp/sloggers_testXXXXXX/logs/foo.log`).
+        let relative_dir = std::env::current_dir()
+            .unwrap()
+            .components()
+            .skip(1)
+            .map(|_| Path::new(".."))
+            .collect::<PathBuf>()
+            .join(dir.path().strip_prefix("/").unwrap());
+        let log_path = relative_dir.join("logs").join("foo.log");
+        let symlink = relative_dir.join("links").join("current.log");
+        fs::create_dir(relative_dir.join("links")).unwrap();
+
+        let logger = FileLoggerBuilder::new(&log_path)
+            .create_dirs(true)
+            .symlink(&symlink)
+            .build()
+            .unwrap();
+        info!(logger, "hello");
+        thread::sleep(Duration::from_millis(50));
+
+        assert!(log_path.is_relative());
+        assert!(fs::read_link(&symlink).unwrap().is_absolute());
+        assert!(fs::read_to_string(&symlink).unwrap().contains("hello"));
+    }
+
+    #[test]
+    fn path_template_rotation_works() {
+        let dir = tempdir();
+        let logger = FileLoggerBuilder::new(dir.path().join("foo_{timestamp}.log"))
+            .enable_path_template("%H%M%S%.6f")
+            .rotate_size(1)
+            .build()
+            .unwrap();
+
+        for i in 0..3 {
+            info!(logger, "hello"; "i" => i);
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        let mut names = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+
+        // Each file contains one record, and the last one is the current (empty) file.
+        assert_eq!(names.len(), 4);
+        for (i, name) in names.iter().enumerate() {
+            assert!(
+                name.starts_with("foo_") && name.ends_with(".log"),
+                "{}",
+                name
+            );
+            let content = fs::read_to_string(dir.path().join(name)).unwrap();
+            if i < 3 {
+                assert!(content.contains(&format!("i: {}", i)));
+            } else {
+                assert!(content.is_empty());
+            }
+        }
+    }
+
     #[test]
     fn test_path_template_to_path() {
         let dir = tempdir();
@@ -667,10 +2921,125 @@ mod tests {
             &path_template,
             "%Y%m%d_%H%M",
             TimeZone::Utc, // Local is difficult to test, omitting :(
-            Utc.from_utc_datetime(&NaiveDateTime::from_timestamp(1537265991, 0)),
+            Utc.timestamp_opt(1537265991, 0).unwrap(),
         );
         let expected = dir.path().join("foo_20180918_1019.log");
         assert_eq!(expected, actual);
+
+        let actual = path_template_to_path(
+            "foo_{date}.log",
+            "%Y%m%d_%H%M",
+            TimeZone::Utc,
+            Utc.timestamp_opt(1537265991, 0).unwrap(),
+        );
+        assert_eq!(Path::new("foo_20180918.log"), actual);
+    }
+
+    #[test]
+    fn path_template_retention_works() {
+        let dir = tempdir();
+        // Files which don't match the template, and a file left by a previous run.
+        let other_paths = ["foo_bar.log", "other-0.log", "foo_2.log.txt"]
+            .iter()
+            .map(|name| dir.path().join(name))
+            .collect::<Vec<_>>();
+        let previous_path = dir.path().join("foo_000000.000000.log.1");
+        for path in other_paths.iter().chain(Some(&previous_path)) {
+            File::create(path).unwrap();
+        }
+
+        let rotate = |cleanup_existing: bool| {
+            let template = dir.path().join("foo_{timestamp}.log");
+            let mut appender = FileAppender::new(&template);
+            appender.set_path_template(
+                template.to_str().unwrap().to_owned(),
+                "%H%M%S%.6f".to_owned(),
+            );
+            appender.rotate_keep = 2;
+            appender.rotate_cleanup_existing = cleanup_existing;
+            for content in &["a", "b", "c", "d"] {
+                appender.write_all(content.as_bytes()).unwrap();
+                appender.flush().unwrap();
+                thread::sleep(Duration::from_millis(10));
+                appender.rotate().unwrap();
+            }
+        };
+        let contents = || {
+            let mut names = fs::read_dir(dir.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+                .filter(|name| name.starts_with("foo_") && !name.starts_with("foo_000000"))
+                .filter(|name| !name.starts_with("foo_bar") && !name.starts_with("foo_2."))
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+                .iter()
+                .map(|name| fs::read_to_string(dir.path().join(name)).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        // The oldest files are deleted, and the current (empty) file and others are kept.
+        rotate(false);
+        assert_eq!(contents(), ["c", "d", ""]);
+        assert!(previous_path.exists());
+
+        // The files of the previous run are also deleted.
+        rotate(true);
+        assert_eq!(contents(), ["c", "d", ""]);
+        assert!(!previous_path.exists());
+        assert!(other_paths.iter().all(|path| path.exists()));
+    }
+
+    #[test]
+    fn test_template_file_base() {
+        let format = path_template_to_format("foo_{timestamp}%.log", "%Y%m%d_%H%M");
+        let base = |name| template_file_base(name, &format, "%Y-%m-%dT%H-%M-%S");
+        assert_eq!(
+            base("foo_20180918_1019%.log"),
+            Some("foo_20180918_1019%.log")
+        );
+        assert_eq!(
+            base("foo_20180918_1019%.log.1.gz"),
+            Some("foo_20180918_1019%.log")
+        );
+        assert_eq!(
+            base("foo_20180918_1019%.log.2018-09-18T10-19-51.2"),
+            Some("foo_20180918_1019%.log")
+        );
+        assert_eq!(base("foo_20180918_1019%.log.gz.temp"), None);
+        assert_eq!(base("foo_20180918_1019%.log.txt"), None);
+        assert_eq!(base("foo_worker%.log"), None);
+    }
+
+    #[test]
+    fn path_template_placeholders_work() {
+        let dir = tempdir();
+        let logger = FileLoggerBuilder::new(dir.path().join("foo_{date}_{pid}.log"))
+            .enable_path_template("%Y%m%d")
+            .build()
+            .unwrap();
+        info!(logger, "hello");
+        thread::sleep(Duration::from_millis(50));
+        let date = format_timestamp("%Y%m%d", TimeZone::Local, Utc::now());
+        assert!(dir
+            .path()
+            .join(format!("foo_{}_{}.log", date, process::id()))
+            .exists());
+
+        // The path is used as it is unless templating is enabled.
+        let logger = FileLoggerBuilder::new(dir.path().join("bar_{pid}.log"))
+            .build()
+            .unwrap();
+        info!(logger, "hello");
+        thread::sleep(Duration::from_millis(50));
+        assert!(dir.path().join("bar_{pid}.log").exists());
+
+        for name in &["foo_${SLOGGERS_TEST_UNDEFINED}.log", "foo_{unknown}.log"] {
+            let result = FileLoggerBuilder::new(dir.path().join(name))
+                .enable_path_template("%Y%m%d")
+                .build();
+            assert!(result.is_err(), "{}", name);
+        }
     }
 
     fn tempdir() -> TempDir {
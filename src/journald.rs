@@ -0,0 +1,671 @@
+//! Logger that sends log records to the systemd journal. Unix-like platforms only.
+//!
+//! Log records are written to the journal's [native protocol] socket, so
+//! key-value pairs are kept as separate journal fields instead of being
+//! flattened into the message (as the syslog logger does).
+//!
+//! [native protocol]: https://systemd.io/JOURNAL_NATIVE_PROTOCOL/
+#![cfg(unix)]
+use crate::build::BuilderCommon;
+#[cfg(feature = "slog-kvfilter")]
+use crate::types::KVFilterParameters;
+use crate::types::{LevelHandle, ModuleFilter, OverflowStrategy, Severity, SourceLocation};
+use crate::{misc, AsyncGuard, Build, Config, Error, Result};
+use serde::{Deserialize, Serialize};
+use slog::{Drain, Key, Level, Logger, OwnedKVList, Record, KV};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::{self, Write as FmtWrite};
+use std::io;
+use std::os::unix::net::UnixDatagram;
+use std::path::{Path, PathBuf};
+use std::result;
+
+const DEFAULT_SOCKET_PATH: &str = "/run/systemd/journal/socket";
+
+/// The fields set by the logger itself, which key-value pairs must not override.
+const RESERVED_FIELDS: &[&str] = &[
+    "MESSAGE",
+    "PRIORITY",
+    "CODE_FILE",
+    "CODE_LINE",
+    "CODE_FUNC",
+    "SYSLOG_IDENTIFIER",
+];
+
+/// The length (in bytes) to which `MESSAGE` is truncated when an entry is too large to send.
+const TRUNCATED_MESSAGE_LEN: usize = 4096;
+
+/// A logger builder which builds loggers that send log records to the systemd journal.
+///
+/// Each log record becomes a journal entry with the following fields:
+///
+/// - `MESSAGE`: the message of the record
+/// - `PRIORITY`: the syslog priority corresponding to the level of the record
+/// - `CODE_FILE` and `CODE_LINE`: the location of the record in the source code
+/// - `CODE_FUNC`: the module path of the record (followed by the function name, if known)
+/// - `SYSLOG_IDENTIFIER`: the identifier set by [`identifier`]
+/// - A field for each key-value pair of the record and the logger
+///
+/// Keys are converted to valid journal field names: they are uppercased, characters
+/// other than ASCII letters and digits are replaced with `_`, and names which don't
+/// start with a letter are prefixed with `F_`.
+/// For example, `user.id` becomes `USER_ID`.
+/// Names of the fields listed above are also prefixed with `F_` (e.g., `priority` becomes
+/// `F_PRIORITY`), so that they don't conflict with the fields set by the logger.
+///
+/// Records which are too large to fit in a single datagram are written to a sealed
+/// memory file whose descriptor is passed to the journal, as `sd_journal_send` does.
+/// This is only possible on Linux; on other platforms (or if it fails), `MESSAGE` is
+/// truncated to 4096 bytes instead, and the record is discarded if it is still too large.
+///
+/// The resulting logger will work asynchronously (the default channel size is 1024).
+///
+/// # Examples
+///
+/// ```
+/// use slog::info;
+/// use sloggers::Build;
+/// use sloggers::journald::JournaldBuilder;
+///
+/// let logger = JournaldBuilder::new().identifier("my-app").build().unwrap();
+/// info!(logger, "Hello World!"; "user" => "alice");
+/// ```
+///
+/// [`identifier`]: #method.identifier
+#[derive(Debug)]
+pub struct JournaldBuilder {
+    common: BuilderCommon,
+    identifier: Option<String>,
+    socket_path: PathBuf,
+}
+impl JournaldBuilder {
+    /// Makes a new `JournaldBuilder` instance.
+    pub fn new() -> Self {
+        JournaldBuilder {
+            common: BuilderCommon {
+                // The location is recorded in the `CODE_*` fields instead.
+                source_location: SourceLocation::None,
+                ..BuilderCommon::default()
+            },
+            identifier: None,
+            socket_path: PathBuf::from(DEFAULT_SOCKET_PATH),
+        }
+    }
+
+    /// Sets the `SYSLOG_IDENTIFIER` field of log records (e.g., for `journalctl -t`).
+    ///
+    /// The default value is the file name of the current executable.
+    pub fn identifier(&mut self, identifier: &str) -> &mut Self {
+        self.identifier = Some(identifier.to_owned());
+        self
+    }
+
+    /// Sets the path of the journal's socket.
+    ///
+    /// The default value is `/run/systemd/journal/socket`.
+    pub fn socket_path<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.socket_path = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// Sets the overflow strategy for the logger.
+    pub fn overflow_strategy(&mut self, overflow_strategy: OverflowStrategy) -> &mut Self {
+        self.common.overflow_strategy = overflow_strategy;
+        self
+    }
+
+    /// Sets the log level of this logger.
+    pub fn level(&mut self, severity: Severity) -> &mut Self {
+        self.common.level = severity;
+        self
+    }
+
+    /// Sets the handle for changing the log level of this logger at runtime.
+    ///
+    /// If this is specified, the log level set by [`level`] is ignored and
+    /// the current level of the handle is used instead.
+    ///
+    /// [`level`]: #method.level
+    pub fn level_handle(&mut self, handle: LevelHandle) -> &mut Self {
+        self.common.level_handle = Some(handle);
+        self
+    }
+
+    /// Sets the per-module log level filter of this logger.
+    ///
+    /// If this is specified, the log level set by [`level`] is only used for the modules
+    /// which match no directive of the filter (and only if the filter has no default level).
+    ///
+    /// [`level`]: #method.level
+    pub fn filter(&mut self, filter: ModuleFilter) -> &mut Self {
+        self.common.filter = Some(filter);
+        self
+    }
+
+    /// Adds a static key-value pair which is attached to all log records of this logger.
+    ///
+    /// This is typically used for attaching the service name or the host name.
+    ///
+    /// The value is used as it is. Placeholders like `{hostname}` are only expanded in
+    /// the `context` table of [`JournaldConfig`].
+    ///
+    /// [`JournaldConfig`]: ./struct.JournaldConfig.html#structfield.context
+    pub fn context(&mut self, key: &str, value: &str) -> &mut Self {
+        self.common.context.push((key.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Sets the size of the asynchronous channel of this logger.
+    pub fn channel_size(&mut self, channel_size: usize) -> &mut Self {
+        self.common.channel_size = channel_size;
+        self
+    }
+
+    /// Sets [`KVFilter`].
+    ///
+    /// [`KVFilter`]: https://docs.rs/slog-kvfilter/0.6/slog_kvfilter/struct.KVFilter.html
+    #[cfg(feature = "slog-kvfilter")]
+    pub fn kvfilter(&mut self, parameters: KVFilterParameters) -> &mut Self {
+        self.common.kvfilterparameters = Some(parameters);
+        self
+    }
+
+    fn build_logger(&self, with_guard: bool) -> Result<(Logger, AsyncGuard)> {
+        let identifier = match self.identifier {
+            Some(ref identifier) => identifier.clone(),
+            None => {
+                let exe = track!(env::current_exe().map_err(Error::from))?;
+                let name = exe.file_name().unwrap_or_default();
+                name.to_string_lossy().into_owned()
+            }
+        };
+        let socket = track!(UnixDatagram::unbound().map_err(Error::from))?;
+        let drain = JournaldDrain {
+            socket,
+            socket_path: self.socket_path.clone(),
+            identifier,
+        };
+        Ok(self.common.build_with_drain(drain, with_guard))
+    }
+}
+impl Default for JournaldBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Build for JournaldBuilder {
+    fn build(&self) -> Result<Logger> {
+        let (logger, _) = track!(self.build_logger(false))?;
+        Ok(logger)
+    }
+
+    fn build_with_guard(&self) -> Result<(Logger, AsyncGuard)> {
+        track!(self.build_logger(true))
+    }
+}
+
+struct JournaldDrain {
+    socket: UnixDatagram,
+    socket_path: PathBuf,
+    identifier: String,
+}
+impl JournaldDrain {
+    /// Formats a journal entry, truncating `MESSAGE` to `message_limit` bytes if given.
+    fn format(
+        &self,
+        record: &Record,
+        values: &OwnedKVList,
+        message_limit: Option<usize>,
+    ) -> result::Result<Vec<u8>, slog::Error> {
+        let mut entry = Vec::new();
+        let mut message = record.msg().to_string();
+        if let Some(limit) = message_limit {
+            if message.len() > limit {
+                let end = (0..=limit)
+                    .rev()
+                    .find(|&i| message.is_char_boundary(i))
+                    .unwrap_or(0);
+                message.truncate(end);
+            }
+        }
+        append_field(&mut entry, "MESSAGE", &message);
+        append_field(&mut entry, "PRIORITY", priority(record.level()));
+        append_field(&mut entry, "CODE_FILE", record.file());
+        append_field(&mut entry, "CODE_LINE", &record.line().to_string());
+        if record.function().is_empty() {
+            append_field(&mut entry, "CODE_FUNC", record.module());
+        } else {
+            let function = format!("{}::{}", record.module(), record.function());
+            append_field(&mut entry, "CODE_FUNC", &function);
+        }
+        append_field(&mut entry, "SYSLOG_IDENTIFIER", &self.identifier);
+
+        let mut serializer = JournaldSerializer { entry: &mut entry };
+        record.kv().serialize(record, &mut serializer)?;
+        values.serialize(record, &mut serializer)?;
+        Ok(entry)
+    }
+
+    /// Sends an entry which is too large to fit in a single datagram.
+    fn send_large(&self, entry: &[u8], record: &Record, values: &OwnedKVList) -> io::Result<()> {
+        #[cfg(target_os = "linux")]
+        {
+            if memfd::send(&self.socket, &self.socket_path, entry).is_ok() {
+                return Ok(());
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = entry;
+
+        let entry = self
+            .format(record, values, Some(TRUNCATED_MESSAGE_LEN))
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        self.socket.send_to(&entry, &self.socket_path).map(|_| ())
+    }
+}
+impl Drain for JournaldDrain {
+    type Ok = ();
+    type Err = slog::Never;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> result::Result<(), slog::Never> {
+        // Errors are ignored, because there is nowhere to report them
+        // (the journal may not be running, for example).
+        if let Ok(entry) = self.format(record, values, None) {
+            if let Err(e) = self.socket.send_to(&entry, &self.socket_path) {
+                if is_too_large(&e) {
+                    let _ = self.send_large(&entry, record, values);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+struct JournaldSerializer<'a> {
+    entry: &'a mut Vec<u8>,
+}
+impl<'a> slog::Serializer for JournaldSerializer<'a> {
+    fn emit_arguments(&mut self, key: Key, val: &fmt::Arguments) -> slog::Result {
+        let mut value = String::new();
+        value.write_fmt(*val)?;
+        append_field(self.entry, &field_name(key), &value);
+        Ok(())
+    }
+}
+
+/// Returns whether sending a datagram failed because it is too large.
+fn is_too_large(error: &io::Error) -> bool {
+    match error.raw_os_error() {
+        Some(code) => code == libc::EMSGSIZE || code == libc::ENOBUFS,
+        None => false,
+    }
+}
+
+/// Passing journal entries in sealed memory files, for entries which are too large to
+/// fit in a single datagram.
+#[cfg(target_os = "linux")]
+mod memfd {
+    use std::fs::File;
+    use std::io::{self, Write};
+    use std::mem;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+    use std::os::unix::net::UnixDatagram;
+    use std::path::Path;
+    use std::ptr;
+
+    /// Writes `entry` to a sealed memory file and sends its descriptor to `path`.
+    pub fn send(socket: &UnixDatagram, path: &Path, entry: &[u8]) -> io::Result<()> {
+        let file = create(entry)?;
+
+        // SAFETY: All-zero bytes are valid for these plain C structures.
+        let mut addr: libc::sockaddr_un = unsafe { mem::zeroed() };
+        addr.sun_family = libc::AF_UNIX as libc::sa_family_t;
+        let path = path.as_os_str().as_bytes();
+        if path.len() >= addr.sun_path.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Socket path is too long",
+            ));
+        }
+        for (dst, &src) in addr.sun_path.iter_mut().zip(path) {
+            *dst = src as libc::c_char;
+        }
+
+        let fd = file.as_raw_fd();
+        // `u64` elements keep the buffer aligned for `cmsghdr`.
+        let mut control = [0u64; 4];
+        // SAFETY: See above.
+        let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+        msg.msg_name = &mut addr as *mut libc::sockaddr_un as *mut libc::c_void;
+        msg.msg_namelen = mem::size_of::<libc::sockaddr_un>() as libc::socklen_t;
+        msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+        // SAFETY: `CMSG_SPACE` and `CMSG_LEN` only compute sizes. `CMSG_FIRSTHDR` points into
+        // `control`, which is large enough for a single descriptor.
+        unsafe {
+            msg.msg_controllen = libc::CMSG_SPACE(mem::size_of::<libc::c_int>() as u32) as _;
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN(mem::size_of::<libc::c_int>() as u32) as _;
+            ptr::write_unaligned(libc::CMSG_DATA(cmsg) as *mut libc::c_int, fd);
+        }
+
+        // SAFETY: `msg` points to valid buffers for the duration of the call.
+        if unsafe { libc::sendmsg(socket.as_raw_fd(), &msg, libc::MSG_NOSIGNAL) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Creates a memory file containing `entry`, sealed against further modification.
+    fn create(entry: &[u8]) -> io::Result<File> {
+        let name = b"sloggers-journald\0";
+        // SAFETY: `name` is a NUL-terminated string.
+        let fd = unsafe {
+            libc::memfd_create(
+                name.as_ptr() as *const libc::c_char,
+                libc::MFD_ALLOW_SEALING | libc::MFD_CLOEXEC,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `fd` is a newly created descriptor owned by nothing else.
+        let mut file = unsafe { File::from_raw_fd(fd) };
+        file.write_all(entry)?;
+
+        let seals =
+            libc::F_SEAL_SHRINK | libc::F_SEAL_GROW | libc::F_SEAL_WRITE | libc::F_SEAL_SEAL;
+        // SAFETY: `fd` is a valid descriptor.
+        if unsafe { libc::fcntl(fd, libc::F_ADD_SEALS, seals) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(file)
+    }
+}
+
+/// Appends a field to a journal entry in the native protocol format.
+fn append_field(entry: &mut Vec<u8>, name: &str, value: &str) {
+    entry.extend_from_slice(name.as_bytes());
+    if value.contains('\n') {
+        // Values containing line breaks are prefixed with their lengths instead.
+        entry.push(b'\n');
+        entry.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        entry.push(b'=');
+    }
+    entry.extend_from_slice(value.as_bytes());
+    entry.push(b'\n');
+}
+
+/// Converts `key` to a valid journal field name.
+fn field_name(key: &str) -> String {
+    let mut name = String::with_capacity(key.len());
+    if !key.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name.push_str("F_");
+    }
+    name.extend(key.chars().map(|c| {
+        if c.is_ascii_alphanumeric() {
+            c.to_ascii_uppercase()
+        } else {
+            '_'
+        }
+    }));
+    if RESERVED_FIELDS.contains(&name.as_str()) {
+        name.insert_str(0, "F_");
+    }
+    name.truncate(64);
+    name
+}
+
+fn priority(level: Level) -> &'static str {
+    match level {
+        Level::Critical => "2",
+        Level::Error => "3",
+        Level::Warning => "4",
+        Level::Info => "6",
+        Level::Debug | Level::Trace => "7",
+    }
+}
+
+/// The configuration of `JournaldBuilder`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+#[serde(default)]
+pub struct JournaldConfig {
+    /// Log level.
+    pub level: Severity,
+
+    /// Per-module log level filter (e.g., `"info,my_crate::db=debug,hyper=warn"`).
+    ///
+    /// For details, see the documentation of [`ModuleFilter`].
+    ///
+    /// [`ModuleFilter`]: ../types/struct.ModuleFilter.html
+    pub filter: Option<ModuleFilter>,
+
+    /// The `SYSLOG_IDENTIFIER` field of log records.
+    ///
+    /// The default value is the file name of the current executable.
+    pub identifier: Option<String>,
+
+    /// The path of the journal's socket.
+    ///
+    /// The default value is `/run/systemd/journal/socket`.
+    pub socket_path: PathBuf,
+
+    /// Asynchronous channel size
+    pub channel_size: usize,
+
+    /// Whether to drop logs on overflow.
+    ///
+    /// The possible values are `drop`, `drop_and_report`, or `block`.
+    ///
+    /// The default value is `drop_and_report`.
+    pub overflow_strategy: OverflowStrategy,
+
+    /// Key-value filter (e.g., `{ severity = "warning", always_suppress_on_regex = "^debug" }`).
+    ///
+    /// For details, see the documentation of [`KVFilterParameters`].
+    ///
+    /// [`KVFilterParameters`]: ../types/struct.KVFilterParameters.html
+    #[cfg(feature = "slog-kvfilter")]
+    pub kvfilter: Option<KVFilterParameters>,
+
+    /// Static key-value pairs attached to all log records (e.g., `{ service = "foo" }`).
+    ///
+    /// The values can contain the following placeholders:
+    /// `{hostname}`, `{pid}`, `{exe}` (the file name of the executable), and
+    /// `${NAME}` (the value of the environment variable `NAME`).
+    pub context: BTreeMap<String, String>,
+}
+impl JournaldConfig {
+    /// Creates a new `JournaldConfig` with default settings.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+impl Default for JournaldConfig {
+    fn default() -> Self {
+        JournaldConfig {
+            level: Severity::default(),
+            filter: None,
+            identifier: None,
+            socket_path: PathBuf::from(DEFAULT_SOCKET_PATH),
+            channel_size: 1024,
+            overflow_strategy: OverflowStrategy::default(),
+            #[cfg(feature = "slog-kvfilter")]
+            kvfilter: None,
+            context: BTreeMap::new(),
+        }
+    }
+}
+impl Config for JournaldConfig {
+    type Builder = JournaldBuilder;
+    fn try_to_builder(&self) -> Result<Self::Builder> {
+        let mut builder = JournaldBuilder::new();
+        builder.level(self.level);
+        if let Some(ref filter) = self.filter {
+            builder.filter(filter.clone());
+        }
+        if let Some(ref identifier) = self.identifier {
+            builder.identifier(identifier);
+        }
+        builder.socket_path(&self.socket_path);
+        builder.channel_size(self.channel_size);
+        builder.overflow_strategy(self.overflow_strategy);
+        #[cfg(feature = "slog-kvfilter")]
+        if let Some(ref parameters) = self.kvfilter {
+            builder.kvfilter(parameters.clone());
+        }
+        for (key, value) in &self.context {
+            builder.context(key, &track!(misc::expand_placeholders(value))?);
+        }
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::Builder as TempDirBuilder;
+
+    /// Parses a journal entry in the native protocol format.
+    fn parse_entry(mut entry: &[u8]) -> Vec<(String, String)> {
+        let mut fields = Vec::new();
+        while !entry.is_empty() {
+            let end = entry.iter().position(|&b| b == b'=' || b == b'\n').unwrap();
+            let name = String::from_utf8(entry[..end].to_vec()).unwrap();
+            let (value, rest) = if entry[end] == b'=' {
+                let len = entry[end..].iter().position(|&b| b == b'\n').unwrap() - 1;
+                entry[end + 1..].split_at(len)
+            } else {
+                let mut len = [0; 8];
+                len.copy_from_slice(&entry[end + 1..end + 9]);
+                entry[end + 9..].split_at(u64::from_le_bytes(len) as usize)
+            };
+            fields.push((name, String::from_utf8(value.to_vec()).unwrap()));
+            assert_eq!(rest[0], b'\n');
+            entry = &rest[1..];
+        }
+        fields
+    }
+
+    #[test]
+    fn journald_logger_works() {
+        let dir = TempDirBuilder::new()
+            .prefix("sloggers_test")
+            .tempdir()
+            .expect("Cannot create a temporary directory");
+        let path = dir.path().join("journal.sock");
+        let server = UnixDatagram::bind(&path).unwrap();
+        server
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+
+        let config: JournaldConfig = serdeconv::from_toml_str(&format!(
+            r#"
+identifier = "my-app"
+socket_path = "{}"
+context = {{ service = "foo" }}
+"#,
+            path.display()
+        ))
+        .unwrap();
+        let (logger, guard) = config.try_to_builder().unwrap().build_with_guard().unwrap();
+        warn!(logger, "Hello"; "user.id" => 42, "_secret" => "multi\nline", "priority" => "high");
+        guard.flush();
+
+        let mut buf = [0; 4096];
+        let size = server.recv(&mut buf).unwrap();
+        let fields = parse_entry(&buf[..size]);
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(field("MESSAGE"), Some("Hello"));
+        assert_eq!(field("PRIORITY"), Some("4"));
+        assert_eq!(field("CODE_FILE"), Some(file!()));
+        assert!(field("CODE_LINE").unwrap().parse::<u32>().is_ok());
+        assert_eq!(field("CODE_FUNC"), Some(module_path!()));
+        assert_eq!(field("SYSLOG_IDENTIFIER"), Some("my-app"));
+        assert_eq!(field("USER_ID"), Some("42"));
+        assert_eq!(field("F__SECRET"), Some("multi\nline"));
+        assert_eq!(field("SERVICE"), Some("foo"));
+        assert_eq!(field("MODULE"), None);
+        assert_eq!(field("F_PRIORITY"), Some("high"));
+        assert_eq!(fields.iter().filter(|(n, _)| n == "PRIORITY").count(), 1);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn journald_logger_sends_large_entries_in_memfd() {
+        use std::fs::File;
+        use std::io::{Read, Seek, SeekFrom};
+        use std::mem;
+        use std::os::unix::io::{AsRawFd, FromRawFd};
+
+        let dir = TempDirBuilder::new()
+            .prefix("sloggers_test")
+            .tempdir()
+            .expect("Cannot create a temporary directory");
+        let path = dir.path().join("journal.sock");
+        let server = UnixDatagram::bind(&path).unwrap();
+        server
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+
+        let (logger, guard) = JournaldBuilder::new()
+            .socket_path(&path)
+            .build_with_guard()
+            .unwrap();
+        let message = "x".repeat(4 * 1024 * 1024);
+        warn!(logger, "{}", message);
+        guard.flush();
+
+        // Receive the datagram along with the passed descriptor.
+        let mut buf = [0u8; 16];
+        let mut iov = libc::iovec {
+            iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+            iov_len: buf.len(),
+        };
+        let mut control = [0u64; 4];
+        let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+        msg.msg_controllen = mem::size_of_val(&control) as _;
+        let size = unsafe { libc::recvmsg(server.as_raw_fd(), &mut msg, 0) };
+        assert_eq!(size, 0);
+        let fd = unsafe {
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            assert!(!cmsg.is_null());
+            assert_eq!((*cmsg).cmsg_type, libc::SCM_RIGHTS);
+            std::ptr::read_unaligned(libc::CMSG_DATA(cmsg) as *const libc::c_int)
+        };
+
+        let mut file = unsafe { File::from_raw_fd(fd) };
+        let mut entry = Vec::new();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file.read_to_end(&mut entry).unwrap();
+        let fields = parse_entry(&entry);
+        assert_eq!(fields[0], ("MESSAGE".to_owned(), message));
+    }
+
+    #[test]
+    fn field_name_works() {
+        assert_eq!(field_name("user"), "USER");
+        assert_eq!(field_name("http.status-code"), "HTTP_STATUS_CODE");
+        assert_eq!(field_name("_foo"), "F__FOO");
+        assert_eq!(field_name("1st"), "F_1ST");
+        assert_eq!(field_name(""), "F_");
+        assert_eq!(field_name("priority"), "F_PRIORITY");
+        assert_eq!(field_name("code.file"), "F_CODE_FILE");
+        assert_eq!(field_name("message_id"), "MESSAGE_ID");
+        assert_eq!(field_name(&"a".repeat(100)).len(), 64);
+    }
+}
@@ -4,9 +4,10 @@
 //! **Important note:** this crate is optimized for performance rather than for
 //! not losing any messages! This may be surprising in some common scenarios,
 //! like logging an error message and calling `std::process::exit(1)`. It's
-//! recommended to drop the logger(s) before exiting. `panic = "abort"` may have
-//! the same surprising effect, so unwinding is preferrable if you want to avoid
-//! losing the messages. See [#29](https://github.com/sile/sloggers/issues/29) for
+//! recommended to drop the logger(s) before exiting, or to build the logger(s) via
+//! [`Build::build_with_guard`] and drop the returned [`AsyncGuard`] before exiting.
+//! `panic = "abort"` may have the same surprising effect, so unwinding is
+//! preferrable if you want to avoid losing the messages. See [#29](https://github.com/sile/sloggers/issues/29) for
 //! more information.
 //!
 //! # Examples
@@ -42,27 +43,45 @@
 //! let logger = config.build_logger().unwrap();
 //! info!(logger, "Hello World!");
 //! ```
+//!
+//! # Features
+//!
+//! The following optional features are available:
+//!
+//! - `libflate` (enabled by default): GZIP compression of rotated log files
+//! - `slog-kvfilter` (enabled by default): key-value filters ([`types::KVFilterParameters`])
+//! - `zstd`: Zstandard compression of rotated log files
+//! - `reload`: loggers which are rebuilt when their configuration files change
+//!   (`reload::ReloadableLogger`)
+//! - `signal-hook` (Unix only): reopening log files on signals like `SIGHUP`
+//!   (`file::ReopenHandle::reopen_on_signal` and `file::FileLoggerConfig::reopen_signal`)
 #![warn(missing_docs)]
 #[macro_use]
 extern crate slog;
 #[macro_use]
 extern crate trackable;
 
-pub use build::{Build, LoggerBuilder};
+pub use build::{AsyncGuard, Build, LoggerBuilder};
 pub use config::{Config, LoggerConfig};
 pub use error::{Error, ErrorKind};
 pub use misc::set_stdlog_logger;
 
 pub mod file;
+pub mod journald;
+pub mod multi;
 pub mod null;
+#[cfg(feature = "reload")]
+pub mod reload;
 pub mod syslog;
 pub mod terminal;
 pub mod types;
 
 mod build;
 mod config;
+mod fake_journald;
 mod fake_syslog;
 mod error;
+mod logfmt;
 mod misc;
 
 /// A specialized `Result` type for this crate.
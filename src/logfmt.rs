@@ -0,0 +1,162 @@
+//! Drain which outputs log records in [logfmt] format.
+//!
+//! [logfmt]: https://brandur.org/logfmt
+use crate::misc;
+use crate::types::TimeZone;
+use slog::{Drain, Key, OwnedKVList, Record, KV};
+use std::cell::RefCell;
+use std::fmt::{self, Write as FmtWrite};
+use std::io;
+
+/// A drain which writes each log record as a single logfmt line.
+///
+/// The line starts with the `ts`, `level` and `msg` keys, followed by
+/// all key-value pairs of the record and the logger.
+pub struct LogfmtDrain<W: io::Write> {
+    io: RefCell<W>,
+    timestamp: fn(&Record) -> String,
+}
+impl<W: io::Write> LogfmtDrain<W> {
+    pub fn new(io: W, timezone: TimeZone) -> Self {
+        LogfmtDrain {
+            io: RefCell::new(io),
+            timestamp: misc::timezone_to_rfc3339_fn(timezone),
+        }
+    }
+
+    fn format(&self, record: &Record, values: &OwnedKVList) -> Result<String, slog::Error> {
+        let mut line = String::new();
+        write!(line, "ts={}", (self.timestamp)(record))?;
+        write!(line, " level={}", record.level().as_str().to_lowercase())?;
+        line.push_str(" msg=");
+        write_value(&mut line, &record.msg().to_string())?;
+
+        let mut serializer = LogfmtSerializer { line: &mut line };
+        record.kv().serialize(record, &mut serializer)?;
+        values.serialize(record, &mut serializer)?;
+
+        line.push('\n');
+        Ok(line)
+    }
+}
+impl<W: io::Write> Drain for LogfmtDrain<W> {
+    type Ok = ();
+    type Err = io::Error;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> io::Result<()> {
+        let line = self
+            .format(record, values)
+            .map_err(|e| io::Error::other(e.to_string()))?;
+
+        // Flushing after every record is required, because `FileAppender` checks
+        // whether the file should be rotated when flushed.
+        let mut io = self.io.borrow_mut();
+        io.write_all(line.as_bytes())?;
+        io.flush()
+    }
+}
+
+struct LogfmtSerializer<'a> {
+    line: &'a mut String,
+}
+impl<'a> slog::Serializer for LogfmtSerializer<'a> {
+    fn emit_arguments(&mut self, key: Key, val: &fmt::Arguments) -> slog::Result {
+        self.line.push(' ');
+        write_key(self.line, key);
+        self.line.push('=');
+        write_value(self.line, &val.to_string())?;
+        Ok(())
+    }
+}
+
+/// Writes `key`, replacing characters which are not allowed in logfmt keys with `_`.
+fn write_key(line: &mut String, key: &str) {
+    if key.is_empty() {
+        line.push('_');
+        return;
+    }
+    line.extend(key.chars().map(|c| {
+        if c == '=' || c == '"' || c.is_whitespace() || c.is_control() {
+            '_'
+        } else {
+            c
+        }
+    }));
+}
+
+/// Writes `value`, quoting and escaping it if needed.
+fn write_value(line: &mut String, value: &str) -> fmt::Result {
+    let needs_quotes = value.is_empty()
+        || value
+            .chars()
+            .any(|c| c == '=' || c == '"' || c == '\\' || c.is_whitespace() || c.is_control());
+    if !needs_quotes {
+        line.push_str(value);
+        return Ok(());
+    }
+
+    line.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => line.push_str("\\\""),
+            '\\' => line.push_str("\\\\"),
+            '\n' => line.push_str("\\n"),
+            '\r' => line.push_str("\\r"),
+            '\t' => line.push_str("\\t"),
+            c if c.is_control() => write!(line, "\\u{:04x}", c as u32)?,
+            c => line.push(c),
+        }
+    }
+    line.push('"');
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slog::Level;
+
+    fn value(s: &str) -> String {
+        let mut line = String::new();
+        write_value(&mut line, s).unwrap();
+        line
+    }
+
+    #[test]
+    fn value_quoting_works() {
+        assert_eq!(value("foo"), "foo");
+        assert_eq!(value("12.5"), "12.5");
+        assert_eq!(value(""), r#""""#);
+        assert_eq!(value("foo bar"), r#""foo bar""#);
+        assert_eq!(value("a=b"), r#""a=b""#);
+        assert_eq!(value(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(value(r"C:\tmp"), r#""C:\\tmp""#);
+        assert_eq!(value("line1\nline2\t\x07"), r#""line1\nline2\t\u0007""#);
+    }
+
+    #[test]
+    fn key_sanitizing_works() {
+        let mut line = String::new();
+        write_key(&mut line, "a key=\"x\"");
+        assert_eq!(line, "a_key__x_");
+    }
+
+    #[test]
+    fn format_works() {
+        let drain = LogfmtDrain::new(Vec::new(), TimeZone::Utc);
+        let line = drain
+            .format(
+                &record!(
+                    Level::Warning,
+                    "",
+                    &format_args!("Hello, world!"),
+                    b!("key1" => "value 1")
+                ),
+                &o!("key2" => 2).into(),
+            )
+            .unwrap();
+
+        assert!(line.starts_with("ts="));
+        assert!(line.ends_with(" level=warning msg=\"Hello, world!\" key1=\"value 1\" key2=2\n"));
+    }
+}
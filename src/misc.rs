@@ -1,8 +1,14 @@
 use crate::types::TimeZone;
-use crate::{ErrorKind, Result};
-use slog::{Logger, Record};
+use crate::{Error, ErrorKind, Result};
+use chrono::{Local, Utc};
+use slog::{FnValue, Logger, PushFnValue, Record, Serializer, KV};
+use slog_json::Json;
+use std::collections::BTreeSet;
+use std::env;
 use std::io;
 use std::path::Path;
+use std::process;
+use std::sync::Mutex;
 use trackable::error::ErrorKindExt;
 
 /// Sets the logger for the log records emitted via `log` crate.
@@ -33,3 +39,131 @@ pub fn timezone_to_timestamp_fn(timezone: TimeZone) -> fn(&mut dyn io::Write) ->
         TimeZone::Local => slog_term::timestamp_local,
     }
 }
+
+pub fn timezone_to_rfc3339_fn(timezone: TimeZone) -> fn(&Record) -> String {
+    match timezone {
+        TimeZone::Utc => |_| Utc::now().to_rfc3339(),
+        TimeZone::Local => |_| Local::now().to_rfc3339(),
+    }
+}
+
+pub fn json_drain<W: io::Write>(writer: W, timezone: TimeZone) -> Json<W> {
+    // Flushing after every record is required, because `FileAppender` checks
+    // whether the file should be rotated when flushed.
+    Json::new(writer)
+        .set_flush(true)
+        .add_key_value(o!(
+            "ts" => FnValue(timezone_to_rfc3339_fn(timezone)),
+            "level" => FnValue(|record: &Record| record.level().as_str()),
+            "msg" => PushFnValue(|record: &Record, ser| ser.emit(record.msg())),
+        ))
+        .build()
+}
+
+/// Expands the placeholders in `template`.
+///
+/// The following placeholders are supported:
+///
+/// - `{hostname}`: the host name
+/// - `{pid}`: the process ID
+/// - `{exe}`: the file name of the current executable
+/// - `${NAME}`: the value of the environment variable `NAME`
+///
+/// Unknown placeholders are left as they are.
+pub fn expand_placeholders(template: &str) -> Result<String> {
+    let mut expanded = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(i) => start + i,
+            None => break,
+        };
+        let name = &rest[start + 1..end];
+        let (prefix, value) = if rest[..start].ends_with('$') {
+            let value = match env::var(name) {
+                Ok(value) => value,
+                Err(e) => track_panic!(
+                    ErrorKind::Invalid,
+                    "Cannot read the environment variable {:?}: {}",
+                    name,
+                    e
+                ),
+            };
+            (&rest[..start - 1], Some(value))
+        } else {
+            (&rest[..start], track!(expand_builtin_placeholder(name))?)
+        };
+        match value {
+            Some(value) => {
+                expanded.push_str(prefix);
+                expanded.push_str(&value);
+            }
+            None => expanded.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn expand_builtin_placeholder(name: &str) -> Result<Option<String>> {
+    match name {
+        "hostname" => {
+            let hostname = track!(hostname::get().map_err(Error::from))?;
+            Ok(Some(hostname.to_string_lossy().into_owned()))
+        }
+        "pid" => Ok(Some(process::id().to_string())),
+        "exe" => {
+            let exe = track!(env::current_exe().map_err(Error::from))?;
+            let name = exe.file_name().unwrap_or_default();
+            Ok(Some(name.to_string_lossy().into_owned()))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Returns a `'static` version of `key`.
+///
+/// `slog` requires the keys of key-value pairs to be `'static`, so the keys given at runtime
+/// (e.g., from a configuration file) are leaked.
+/// Each distinct key is leaked only once.
+pub fn intern_key(key: &str) -> &'static str {
+    static KEYS: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+    let mut keys = KEYS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(key) = keys.get(key) {
+        return key;
+    }
+    let key: &'static str = Box::leak(key.to_owned().into_boxed_str());
+    keys.insert(key);
+    key
+}
+
+/// Static key-value pairs added to the root logger.
+pub struct ContextKV(pub Vec<(&'static str, String)>);
+impl KV for ContextKV {
+    fn serialize(&self, _record: &Record, serializer: &mut dyn Serializer) -> slog::Result {
+        for (key, value) in &self.0 {
+            serializer.emit_str(key, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_placeholders_works() {
+        // `PATH` is read instead of setting a variable, because tests run in parallel.
+        let expanded = expand_placeholders("${PATH}-{pid}").unwrap();
+        let path = env::var("PATH").unwrap();
+        assert_eq!(expanded, format!("{}-{}", path, process::id()));
+
+        let expanded = expand_placeholders("{unknown} {pid").unwrap();
+        assert_eq!(expanded, "{unknown} {pid");
+
+        assert!(expand_placeholders("${SLOGGERS_TEST_UNDEFINED}").is_err());
+    }
+}
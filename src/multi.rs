@@ -0,0 +1,149 @@
+//! Multi-destination logger.
+use crate::{AsyncGuard, Build, Config, LoggerBuilder, LoggerConfig, Result};
+use serde::{Deserialize, Serialize};
+use slog::{Drain, Logger, OwnedKVList, Record};
+
+/// A logger builder which builds loggers that send each log record to several loggers.
+///
+/// Each child logger keeps its own settings (e.g., log level and format),
+/// so a record is only outputted by the children which accept it.
+///
+/// # Examples
+///
+/// ```
+/// use slog::info;
+/// use sloggers::{Build, LoggerBuilder};
+/// use sloggers::multi::MultiLoggerBuilder;
+/// use sloggers::null::NullLoggerBuilder;
+/// use sloggers::terminal::TerminalLoggerBuilder;
+/// use sloggers::types::Severity;
+///
+/// let mut terminal = TerminalLoggerBuilder::new();
+/// terminal.level(Severity::Warning);
+///
+/// let logger = MultiLoggerBuilder::new()
+///     .logger(LoggerBuilder::Terminal(terminal))
+///     .logger(LoggerBuilder::Null(NullLoggerBuilder))
+///     .build()
+///     .unwrap();
+/// info!(logger, "Hello World!");
+/// ```
+#[derive(Debug, Default)]
+pub struct MultiLoggerBuilder {
+    loggers: Vec<LoggerBuilder>,
+}
+impl MultiLoggerBuilder {
+    /// Makes a new `MultiLoggerBuilder` instance which has no child loggers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a child logger builder.
+    pub fn logger(&mut self, builder: LoggerBuilder) -> &mut Self {
+        self.loggers.push(builder);
+        self
+    }
+}
+impl Build for MultiLoggerBuilder {
+    fn build(&self) -> Result<Logger> {
+        let loggers = self
+            .loggers
+            .iter()
+            .map(|b| track!(b.build()))
+            .collect::<Result<Vec<_>>>()?;
+        let logger = Logger::root(MultiDrain(loggers), o!());
+        Ok(logger)
+    }
+
+    fn build_with_guard(&self) -> Result<(Logger, AsyncGuard)> {
+        let mut loggers = Vec::with_capacity(self.loggers.len());
+        let mut guard = AsyncGuard::default();
+        for builder in &self.loggers {
+            let (logger, g) = track!(builder.build_with_guard())?;
+            loggers.push(logger);
+            guard.merge(g);
+        }
+        let logger = Logger::root(MultiDrain(loggers), o!());
+        Ok((logger, guard))
+    }
+}
+
+struct MultiDrain(Vec<Logger>);
+impl Drain for MultiDrain {
+    type Ok = ();
+    type Err = slog::Never;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> std::result::Result<(), slog::Never> {
+        for logger in &self.0 {
+            Drain::log(logger, record, values)?;
+        }
+        Ok(())
+    }
+}
+
+/// The configuration of `MultiLoggerBuilder`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct MultiLoggerConfig {
+    /// Configurations of the child loggers.
+    #[serde(default)]
+    pub loggers: Vec<LoggerConfig>,
+}
+impl MultiLoggerConfig {
+    /// Creates a new `MultiLoggerConfig` which has no child loggers.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+impl Config for MultiLoggerConfig {
+    type Builder = MultiLoggerBuilder;
+    fn try_to_builder(&self) -> Result<Self::Builder> {
+        let mut builder = MultiLoggerBuilder::new();
+        for config in &self.loggers {
+            builder.logger(track!(config.try_to_builder())?);
+        }
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::FileLoggerBuilder;
+    use crate::types::Severity;
+    use std::fs;
+    use std::thread;
+    use std::time::Duration;
+    use tempfile::Builder as TempDirBuilder;
+
+    #[test]
+    fn multi_logger_works() {
+        let dir = TempDirBuilder::new()
+            .prefix("sloggers_test")
+            .tempdir()
+            .expect("Cannot create a temporary directory");
+        let mut info = FileLoggerBuilder::new(dir.path().join("info.log"));
+        info.level(Severity::Info);
+        let mut debug = FileLoggerBuilder::new(dir.path().join("debug.log"));
+        debug.level(Severity::Debug);
+
+        let logger = MultiLoggerBuilder::new()
+            .logger(LoggerBuilder::File(info))
+            .logger(LoggerBuilder::File(debug))
+            .build()
+            .unwrap();
+        let logger = logger.new(o!("key" => "value"));
+
+        debug!(logger, "foo");
+        info!(logger, "bar");
+        thread::sleep(Duration::from_millis(50));
+
+        let info_log = fs::read_to_string(dir.path().join("info.log")).unwrap();
+        let debug_log = fs::read_to_string(dir.path().join("debug.log")).unwrap();
+        assert!(!info_log.contains("foo"));
+        assert!(info_log.contains("bar"));
+        assert!(info_log.contains("key: value"));
+        assert!(debug_log.contains("foo"));
+        assert!(debug_log.contains("bar"));
+    }
+}
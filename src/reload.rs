@@ -0,0 +1,265 @@
+//! Logger which can be reloaded from its configuration file at runtime.
+//!
+//! This module is only available if the `reload` feature is enabled.
+use crate::{Config, Error, ErrorKind, LoggerConfig, Result};
+use slog::{Drain, Logger, OwnedKVList, Record};
+use slog_atomic::{AtomicSwitch, AtomicSwitchCtrl};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::result;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use trackable::error::ErrorKindExt;
+
+/// A logger which is rebuilt when its configuration file is modified.
+///
+/// The configuration file is a TOML file which represents a [`LoggerConfig`].
+///
+/// The underlying logger is replaced atomically, so the existing `Logger` handles
+/// (including their child loggers) remain valid and send log records to the new logger
+/// after reloading.
+///
+/// [`LoggerConfig`]: ../enum.LoggerConfig.html
+///
+/// # Examples
+///
+/// ```no_run
+/// use slog::info;
+/// use sloggers::reload::ReloadableLogger;
+/// use std::time::Duration;
+///
+/// let reloadable = ReloadableLogger::new("/path/to/logger.toml").unwrap();
+///
+/// // Checks the configuration file every five seconds.
+/// reloadable.watch(Duration::from_secs(5));
+///
+/// let logger = reloadable.logger().clone();
+/// info!(logger, "Hello World!");
+/// ```
+#[derive(Clone)]
+pub struct ReloadableLogger {
+    logger: Logger,
+    inner: Arc<Inner>,
+}
+impl ReloadableLogger {
+    /// Makes a new `ReloadableLogger` instance from the configuration file at `path`.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let modified = modified_time(&path);
+        let switch = AtomicSwitch::new(track!(load_logger(&path))?);
+        let inner = Arc::new(Inner {
+            path,
+            ctrl: switch.ctrl(),
+            modified: Mutex::new(modified),
+        });
+        let drain = ReloadableDrain {
+            switch,
+            _inner: Arc::clone(&inner),
+        };
+        let logger = Logger::root(drain, o!());
+        Ok(ReloadableLogger { logger, inner })
+    }
+
+    /// Returns a reference to the logger.
+    pub fn logger(&self) -> &Logger {
+        &self.logger
+    }
+
+    /// Returns the path of the configuration file.
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// Reloads the configuration file and replaces the underlying logger.
+    ///
+    /// If the configuration file cannot be loaded, the current logger is kept as it is.
+    pub fn reload(&self) -> Result<()> {
+        track!(self.inner.reload())
+    }
+
+    /// Reloads the configuration file if it has been modified since it was loaded last time.
+    ///
+    /// Returns `true` if the logger is reloaded.
+    pub fn reload_if_modified(&self) -> Result<bool> {
+        track!(self.inner.reload_if_modified())
+    }
+
+    /// Spawns a thread which checks the configuration file at the given interval,
+    /// and reloads the logger if the file has been modified.
+    ///
+    /// Errors occurred while reloading are logged to the current logger.
+    ///
+    /// The thread exits when all clones of this `ReloadableLogger` and all loggers obtained
+    /// from [`logger`] (including their child loggers) are dropped.
+    ///
+    /// [`logger`]: #method.logger
+    pub fn watch(&self, interval: Duration) {
+        let inner = Arc::downgrade(&self.inner);
+        // This logger doesn't keep `inner` alive, so that the thread can exit.
+        let logger = Logger::root(self.inner.ctrl.drain(), o!());
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+            let inner = match inner.upgrade() {
+                Some(inner) => inner,
+                None => break,
+            };
+            if let Err(e) = inner.reload_if_modified() {
+                error!(logger, "Cannot reload the logger configuration";
+                       "path" => %inner.path.display(), "error" => %e);
+            }
+        });
+    }
+}
+impl fmt::Debug for ReloadableLogger {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReloadableLogger")
+            .field("logger", &self.logger)
+            .field("path", &self.inner.path)
+            .finish()
+    }
+}
+
+/// A drain which keeps `Inner` alive as long as the loggers of `ReloadableLogger` are in use,
+/// so that the thread spawned by `ReloadableLogger::watch` keeps running.
+struct ReloadableDrain {
+    switch: AtomicSwitch,
+    _inner: Arc<Inner>,
+}
+impl Drain for ReloadableDrain {
+    type Ok = ();
+    type Err = slog::Never;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> result::Result<(), slog::Never> {
+        self.switch.log(record, values)
+    }
+}
+
+struct Inner {
+    path: PathBuf,
+    ctrl: AtomicSwitchCtrl,
+    modified: Mutex<Option<SystemTime>>,
+}
+impl Inner {
+    fn reload(&self) -> Result<()> {
+        // The modification time is read before loading,
+        // so that modifications made while loading are detected next time.
+        let modified = modified_time(&self.path);
+        let logger = track!(load_logger(&self.path))?;
+        self.ctrl.set(logger);
+        *self.modified.lock().unwrap_or_else(|e| e.into_inner()) = modified;
+        Ok(())
+    }
+
+    fn reload_if_modified(&self) -> Result<bool> {
+        let modified = modified_time(&self.path);
+        if modified == *self.modified.lock().unwrap_or_else(|e| e.into_inner()) {
+            return Ok(false);
+        }
+        track!(self.reload())?;
+        Ok(true)
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn load_logger(path: &Path) -> Result<Logger> {
+    let config: LoggerConfig =
+        track!(serdeconv::from_toml_file(path)
+            .map_err(|e| Error::from(ErrorKind::Invalid.takes_over(e))))?;
+    let logger = track!(config.build_logger())?;
+    Ok(logger)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use tempfile::Builder as TempDirBuilder;
+
+    fn write_config(config_path: &Path, log_path: &Path, level: &str) {
+        let config = format!(
+            "type = \"file\"\npath = {:?}\nlevel = {:?}\n",
+            log_path, level
+        );
+        fs::write(config_path, config).unwrap();
+    }
+
+    #[test]
+    fn reload_works() {
+        let dir = TempDirBuilder::new()
+            .prefix("sloggers_test")
+            .tempdir()
+            .expect("Cannot create a temporary directory");
+        let config_path = dir.path().join("logger.toml");
+        let log_path = dir.path().join("foo.log");
+        write_config(&config_path, &log_path, "info");
+
+        let reloadable = ReloadableLogger::new(&config_path).unwrap();
+        let logger = reloadable.logger().new(o!("key" => "value"));
+        assert!(!reloadable.reload_if_modified().unwrap());
+
+        debug!(logger, "foo");
+        write_config(&config_path, &log_path, "debug");
+        reloadable.reload().unwrap();
+        debug!(logger, "bar");
+        thread::sleep(Duration::from_millis(50));
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert!(!content.contains("foo"));
+        assert!(content.contains("bar"));
+        assert!(content.contains("key: value"));
+
+        // Modifications are detected by the modification time.
+        thread::sleep(Duration::from_millis(10));
+        write_config(&config_path, &log_path, "debug");
+        assert!(reloadable.reload_if_modified().unwrap());
+        assert!(!reloadable.reload_if_modified().unwrap());
+
+        // Broken configurations are rejected, and the current logger is kept.
+        fs::write(&config_path, "type = \"unknown\"").unwrap();
+        assert!(reloadable.reload().is_err());
+        debug!(logger, "baz");
+        thread::sleep(Duration::from_millis(50));
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert!(content.contains("baz"));
+    }
+
+    #[test]
+    fn watch_works() {
+        let dir = TempDirBuilder::new()
+            .prefix("sloggers_test")
+            .tempdir()
+            .expect("Cannot create a temporary directory");
+        let config_path = dir.path().join("logger.toml");
+        let log_path = dir.path().join("foo.log");
+        write_config(&config_path, &log_path, "info");
+
+        let reloadable = ReloadableLogger::new(&config_path).unwrap();
+        reloadable.watch(Duration::from_millis(10));
+
+        // Only a clone of the logger is kept, as in the documented example.
+        let logger = reloadable.logger().clone();
+        drop(reloadable);
+
+        debug!(logger, "foo");
+        thread::sleep(Duration::from_millis(50));
+        write_config(&config_path, &log_path, "debug");
+
+        let mut content = String::new();
+        for _ in 0..100 {
+            debug!(logger, "bar");
+            thread::sleep(Duration::from_millis(50));
+            content = fs::read_to_string(&log_path).unwrap_or_default();
+            if content.contains("bar") {
+                break;
+            }
+        }
+        assert!(content.contains("bar"));
+        assert!(!content.contains("foo"));
+    }
+}
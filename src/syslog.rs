@@ -1,8 +1,18 @@
 //! Logger that sends logs to local syslog daemon. Unix-like platforms only.
-//! Uses the [POSIX syslog API].
+//! Uses the [POSIX syslog API] by default.
 //! 
 //! [POSIX syslog API]: https://pubs.opengroup.org/onlinepubs/9699919799/functions/closelog.html
 //! 
+//! Alternatively, log messages can be formatted according to [RFC 5424] or
+//! [RFC 3164] and sent over a UDP, TCP, or Unix domain socket, such as to a
+//! remote collector. See [`Transport`] and [`Protocol`] for details. The
+//! concurrency issues below don't apply to these transports.
+//! 
+//! [RFC 5424]: https://tools.ietf.org/html/rfc5424
+//! [RFC 3164]: https://tools.ietf.org/html/rfc3164
+//! [`Transport`]: enum.Transport.html
+//! [`Protocol`]: enum.Protocol.html
+//! 
 //! # Concurrency issues
 //! 
 //! POSIX doesn't support opening more than one connection to syslogd at a
@@ -101,6 +111,15 @@ use drain::*;
 mod facility;
 pub use facility::*;
 
+mod network;
+use network::*;
+
+mod priority;
+pub use priority::*;
+
+mod transport;
+pub use transport::*;
+
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -1,7 +1,7 @@
-use crate::Build;
+use crate::{AsyncGuard, Build};
 use crate::build::BuilderCommon;
 use crate::Result;
-use crate::types::{OverflowStrategy, Severity, SourceLocation};
+use crate::types::{LevelHandle, ModuleFilter, OverflowStrategy, Severity, SourceLocation};
 #[cfg(feature = "slog-kvfilter")]
 use crate::types::KVFilterParameters;
 use slog::Logger;
@@ -9,7 +9,7 @@ use std::borrow::Cow;
 use std::ffi::{CStr, CString};
 use std::fmt::Debug;
 use std::sync::Arc;
-use super::{Facility, SyslogDrain};
+use super::{Facility, NetworkDrain, PriorityMap, Protocol, SyslogDrain, Transport};
 use super::format::{DefaultMsgFormat, MsgFormat};
 
 /// A logger builder which builds loggers that send log records to a syslog server.
@@ -48,6 +48,12 @@ pub struct SyslogBuilder {
     pub(super) ident: Option<Cow<'static, CStr>>,
     pub(super) option: libc::c_int,
     pub(super) format: Arc<dyn MsgFormat>,
+    pub(super) priority_map: PriorityMap,
+    pub(super) transport: Transport,
+    pub(super) protocol: Protocol,
+    pub(super) max_message_len: Option<usize>,
+    pub(super) hostname: Option<String>,
+    pub(super) msgid: Option<String>,
 }
 
 impl Default for SyslogBuilder {
@@ -58,6 +64,12 @@ impl Default for SyslogBuilder {
             ident: None,
             option: 0,
             format: Arc::new(DefaultMsgFormat),
+            priority_map: PriorityMap::default(),
+            transport: Transport::default(),
+            protocol: Protocol::default(),
+            max_message_len: None,
+            hostname: None,
+            msgid: None,
         }
     }
 }
@@ -338,12 +350,176 @@ impl SyslogBuilder {
         self
     }
 
+    /// Sets the syslog priority of log records of each level.
+    /// 
+    /// By default, `critical` records have the priority `crit`, `error`
+    /// records have `err`, `warning` records have `warning`, `info` records
+    /// have `info`, and `debug` and `trace` records have `debug`.
+    /// 
+    /// The priority of an individual record can also be overridden with the
+    /// [`PRIORITY_KEY`] key.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use sloggers::Build;
+    /// use sloggers::syslog::{Priority, PriorityMap, SyslogBuilder};
+    /// 
+    /// let mut priority_map = PriorityMap::new();
+    /// priority_map.critical = Priority::Alert;
+    /// priority_map.info = Priority::Notice;
+    /// 
+    /// let logger = SyslogBuilder::new()
+    ///     .priority_map(priority_map)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    /// 
+    /// [`PRIORITY_KEY`]: constant.PRIORITY_KEY.html
+    pub fn priority_map(&mut self, priority_map: PriorityMap) -> &mut Self {
+        self.priority_map = priority_map;
+        self
+    }
+
+    /// Sets the way log messages are sent to the syslog server.
+    /// 
+    /// By default, log messages are submitted using the POSIX `syslog`
+    /// function. With the other transports, messages are formatted according
+    /// to [RFC 5424] (or RFC 3164, see [`protocol`]) and sent over a socket,
+    /// so they can be sent to a remote collector.
+    /// 
+    /// When using a socket transport, the `ident` becomes the APP-NAME field,
+    /// and `log_pid` causes the PROCID field to be filled in. The other
+    /// `log_*` flags have no effect.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use sloggers::Build;
+    /// use sloggers::syslog::{SyslogBuilder, Transport};
+    /// 
+    /// let logger = SyslogBuilder::new()
+    ///     .transport(Transport::Tcp("logs.example.com:601".to_owned()))
+    ///     .msgid("audit")
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    /// 
+    /// [RFC 5424]: https://tools.ietf.org/html/rfc5424
+    /// [`protocol`]: #method.protocol
+    pub fn transport(&mut self, transport: Transport) -> &mut Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Sets the format of log messages sent over a socket.
+    /// 
+    /// By default, messages are formatted according to [RFC 5424]. Use
+    /// `Protocol::Rfc3164` for legacy collectors that only accept BSD-style
+    /// messages.
+    /// 
+    /// This is only used by socket transports (see [`transport`]).
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// use sloggers::Build;
+    /// use sloggers::syslog::{Protocol, SyslogBuilder, Transport};
+    /// 
+    /// let logger = SyslogBuilder::new()
+    ///     .transport(Transport::Udp("192.0.2.1:514".to_owned()))
+    ///     .protocol(Protocol::Rfc3164)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    /// 
+    /// [RFC 5424]: https://tools.ietf.org/html/rfc5424
+    /// [`transport`]: #method.transport
+    pub fn protocol(&mut self, protocol: Protocol) -> &mut Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Sets the maximum length of log messages in bytes, including the
+    /// header. Longer messages are truncated.
+    /// 
+    /// By default, RFC 3164 messages are truncated to 1024 bytes, and RFC
+    /// 5424 messages are not truncated.
+    /// 
+    /// This is only used by socket transports (see [`transport`]).
+    /// 
+    /// [`transport`]: #method.transport
+    pub fn max_message_len(&mut self, len: usize) -> &mut Self {
+        self.max_message_len = Some(len);
+        self
+    }
+
+    /// Sets the HOSTNAME field of log messages.
+    /// 
+    /// By default, the host name of this machine is used.
+    /// 
+    /// This is only used by socket transports (see [`transport`]).
+    /// 
+    /// [`transport`]: #method.transport
+    pub fn hostname(&mut self, hostname: impl Into<String>) -> &mut Self {
+        self.hostname = Some(hostname.into());
+        self
+    }
+
+    /// Sets the MSGID field of log messages, which identifies the type of the
+    /// messages (e.g., `audit`).
+    /// 
+    /// By default, there is no MSGID.
+    /// 
+    /// This is only used by socket transports (see [`transport`]).
+    /// 
+    /// [`transport`]: #method.transport
+    pub fn msgid(&mut self, msgid: impl Into<String>) -> &mut Self {
+        self.msgid = Some(msgid.into());
+        self
+    }
+
     /// Sets the log level of this logger.
     pub fn level(&mut self, severity: Severity) -> &mut Self {
         self.common.level = severity;
         self
     }
 
+    /// Sets the handle for changing the log level of this logger at runtime.
+    /// 
+    /// If this is specified, the log level set by [`level`] is ignored and
+    /// the current level of the handle is used instead.
+    /// 
+    /// [`level`]: #method.level
+    pub fn level_handle(&mut self, handle: LevelHandle) -> &mut Self {
+        self.common.level_handle = Some(handle);
+        self
+    }
+
+    /// Sets the per-module log level filter of this logger.
+    /// 
+    /// If this is specified, the log level set by [`level`] is only used for the modules
+    /// which match no directive of the filter (and only if the filter has no default level).
+    /// 
+    /// [`level`]: #method.level
+    pub fn filter(&mut self, filter: ModuleFilter) -> &mut Self {
+        self.common.filter = Some(filter);
+        self
+    }
+
+    /// Adds a static key-value pair which is attached to all log records of this logger.
+    /// 
+    /// This is typically used for attaching the service name or the host name.
+    /// 
+    /// The value is used as it is. Placeholders like `{hostname}` are only expanded in
+    /// the `context` table of [`SyslogConfig`].
+    /// 
+    /// [`SyslogConfig`]: ./struct.SyslogConfig.html#structfield.context
+    pub fn context(&mut self, key: &str, value: &str) -> &mut Self {
+        self.common.context.push((key.to_owned(), value.to_owned()));
+        self
+    }
+
     /// Sets the size of the asynchronous channel of this logger.
     pub fn channel_size(&mut self, channel_size: usize) -> &mut Self {
         self.common.channel_size = channel_size;
@@ -360,10 +536,26 @@ impl SyslogBuilder {
     }
 }
 
+impl SyslogBuilder {
+    fn build_logger(&self, with_guard: bool) -> Result<(Logger, AsyncGuard)> {
+        if self.transport == Transport::Libc {
+            let drain = SyslogDrain::new(self);
+            Ok(self.common.build_with_drain(drain, with_guard))
+        }
+        else {
+            let drain = track!(NetworkDrain::new(self))?;
+            Ok(self.common.build_with_drain(drain, with_guard))
+        }
+    }
+}
+
 impl Build for SyslogBuilder {
     fn build(&self) -> Result<Logger> {
-        let drain = SyslogDrain::new(self);
-        let logger = self.common.build_with_drain(drain);
+        let (logger, _) = track!(self.build_logger(false))?;
         Ok(logger)
     }
+
+    fn build_with_guard(&self) -> Result<(Logger, AsyncGuard)> {
+        track!(self.build_logger(true))
+    }
 }
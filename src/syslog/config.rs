@@ -1,10 +1,13 @@
-use crate::Config;
-use crate::types::{OverflowStrategy, Severity, SourceLocation};
+use crate::{misc, Config};
+#[cfg(feature = "slog-kvfilter")]
+use crate::types::KVFilterParameters;
+use crate::types::{ModuleFilter, OverflowStrategy, Severity, SourceLocation};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::ffi::CStr;
-use super::{Facility, SyslogBuilder};
-use super::format::MsgFormatConfig;
+use super::{Facility, PriorityMap, Protocol, SyslogBuilder, Transport};
+use super::format::{MsgFormatConfig, StructuredDataMsgFormat};
 
 /// The configuration of `SyslogBuilder`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -14,21 +17,80 @@ pub struct SyslogConfig {
     /// Log level.
     pub level: Severity,
 
+    /// Per-module log level filter (e.g., `"info,my_crate::db=debug,hyper=warn"`).
+    /// 
+    /// For details, see the documentation of [`ModuleFilter`].
+    /// 
+    /// [`ModuleFilter`]: ../types/struct.ModuleFilter.html
+    pub filter: Option<ModuleFilter>,
+
     /// How to format syslog messages with structured data.
     /// 
-    /// Possible values are `default` and `basic`.
+    /// Possible values are `default`, `basic`, and `structured_data`.
     /// 
     /// See [`MsgFormat`] for more information.
     /// 
     /// [`MsgFormat`]: format/trait.MsgFormat.html
     pub format: MsgFormatConfig,
 
+    /// The name part of the SD-ID used by the `structured_data` format.
+    /// 
+    /// The default is `slog`.
+    pub sd_name: String,
+
+    /// The IANA private enterprise number part of the SD-ID used by the
+    /// `structured_data` format.
+    /// 
+    /// The default is `32473`, which is reserved for use in documentation.
+    /// See [`StructuredDataMsgFormat`] for more information.
+    /// 
+    /// [`StructuredDataMsgFormat`]: format/struct.StructuredDataMsgFormat.html
+    pub enterprise_number: u32,
+
     /// Source code location
     pub source_location: SourceLocation,
 
     /// The syslog facility to send logs to.
     pub facility: Facility,
 
+    /// The syslog priority of log records of each level
+    /// (e.g., `{ critical = "alert", info = "notice" }`).
+    /// 
+    /// See [`PriorityMap`] for more information.
+    /// 
+    /// [`PriorityMap`]: struct.PriorityMap.html
+    pub priority_map: PriorityMap,
+
+    /// The way log messages are sent to the syslog server.
+    /// 
+    /// Possible values are `libc`, `udp://HOST:PORT`, `tcp://HOST:PORT`, and
+    /// `unix://PATH`.
+    /// 
+    /// The default is `libc`. See [`Transport`] for more information.
+    /// 
+    /// [`Transport`]: enum.Transport.html
+    pub transport: Transport,
+
+    /// The format of log messages sent over a socket.
+    /// 
+    /// Possible values are `rfc5424` and `rfc3164`. The default is `rfc5424`.
+    pub protocol: Protocol,
+
+    /// The maximum length of log messages sent over a socket, in bytes.
+    /// Longer messages are truncated.
+    /// 
+    /// By default, RFC 3164 messages are truncated to 1024 bytes, and RFC
+    /// 5424 messages are not truncated.
+    pub max_message_len: Option<usize>,
+
+    /// The HOSTNAME field of log messages. Only used by socket transports.
+    /// 
+    /// By default, the host name of this machine is used.
+    pub hostname: Option<String>,
+
+    /// The MSGID field of log messages. Only used by socket transports.
+    pub msgid: Option<String>,
+
     /// Asynchronous channel size
     pub channel_size: usize,
 
@@ -94,6 +156,21 @@ pub struct SyslogConfig {
     /// writing to `stderr`, so even on GNU libc, using this option may result 
     /// in garbled output.
     pub log_perror: bool,
+
+    /// Key-value filter (e.g., `{ severity = "warning", always_suppress_on_regex = "^debug" }`).
+    /// 
+    /// For details, see the documentation of [`KVFilterParameters`].
+    /// 
+    /// [`KVFilterParameters`]: ../types/struct.KVFilterParameters.html
+    #[cfg(feature = "slog-kvfilter")]
+    pub kvfilter: Option<KVFilterParameters>,
+
+    /// Static key-value pairs attached to all log records (e.g., `{ service = "foo" }`).
+    /// 
+    /// The values can contain the following placeholders:
+    /// `{hostname}`, `{pid}`, `{exe}` (the file name of the executable), and
+    /// `${NAME}` (the value of the environment variable `NAME`).
+    pub context: BTreeMap<String, String>,
 }
 
 impl SyslogConfig {
@@ -107,15 +184,27 @@ impl Default for SyslogConfig {
     fn default() -> Self {
         SyslogConfig {
             level: Severity::default(),
+            filter: None,
             format: MsgFormatConfig::default(),
+            sd_name: StructuredDataMsgFormat::DEFAULT_NAME.to_owned(),
+            enterprise_number: StructuredDataMsgFormat::DEFAULT_ENTERPRISE_NUMBER,
             source_location: SourceLocation::default(),
             facility: Facility::default(),
+            priority_map: PriorityMap::default(),
+            transport: Transport::default(),
+            protocol: Protocol::default(),
+            max_message_len: None,
+            hostname: None,
+            msgid: None,
             channel_size: 1024,
             overflow_strategy: OverflowStrategy::default(),
             ident: None,
             log_pid: false,
             log_delay: None,
             log_perror: false,
+            #[cfg(feature = "slog-kvfilter")]
+            kvfilter: None,
+            context: BTreeMap::new(),
         }
     }
 }
@@ -127,16 +216,42 @@ impl Config for SyslogConfig {
         let mut b = SyslogBuilder::new();
 
         b.level(self.level);
+        if let Some(filter) = &self.filter {
+            b.filter(filter.clone());
+        }
         b.source_location(self.source_location);
         b.facility(self.facility);
+        b.priority_map(self.priority_map.clone());
+        b.transport(self.transport.clone());
+        b.protocol(self.protocol);
+        if let Some(len) = self.max_message_len {
+            b.max_message_len(len);
+        }
+        if let Some(hostname) = &self.hostname {
+            b.hostname(hostname.clone());
+        }
+        if let Some(msgid) = &self.msgid {
+            b.msgid(msgid.clone());
+        }
         b.channel_size(self.channel_size);
         b.overflow_strategy(self.overflow_strategy);
 
+        #[cfg(feature = "slog-kvfilter")]
+        if let Some(parameters) = &self.kvfilter {
+            b.kvfilter(parameters.clone());
+        }
+        for (key, value) in &self.context {
+            b.context(key, &track!(misc::expand_placeholders(value))?);
+        }
+
         // Don't make this call if not using a non-default format, or there
         // will be an unnecessary extra allocation. `SyslogBuilder::new`
         // already allocates an `Arc<dyn MsgFormat>`, and this call allocates
         // another one.
-        if self.format != MsgFormatConfig::Default {
+        if self.format == MsgFormatConfig::StructuredData {
+            b.format(track!(StructuredDataMsgFormat::new(&self.sd_name, self.enterprise_number))?);
+        }
+        else if self.format != MsgFormatConfig::Default {
             b.format_arc((&self.format).into());
         }
 
@@ -1,13 +1,13 @@
 use libc::{c_char, c_int};
 use once_cell::sync::Lazy;
-use slog::{Drain, Level, Record, OwnedKVList};
+use slog::{Drain, Record, OwnedKVList};
 use std::borrow::Cow;
 use std::ffi::{CStr, CString};
 use std::ptr;
 use std::result::Result as StdResult;
 use std::sync::{Arc, Mutex, MutexGuard};
 use super::format::MsgFormat;
-use super::SyslogBuilder;
+use super::{PriorityMap, SyslogBuilder};
 
 #[cfg(not(test))]
 use libc::{closelog, openlog, syslog};
@@ -59,6 +59,9 @@ pub(super) struct SyslogDrain {
 
     /// The format for log messages.
     format: Arc<dyn MsgFormat>,
+
+    /// The syslog priority of each log level.
+    priority_map: PriorityMap,
 }
 
 impl SyslogDrain {
@@ -109,6 +112,7 @@ impl SyslogDrain {
         SyslogDrain {
             unique_ident,
             format: builder.format.clone(),
+            priority_map: builder.priority_map.clone(),
         }
     }
 }
@@ -205,17 +209,7 @@ impl Drain for SyslogDrain {
         let fmt_err = fmt_err.map(to_cstring_lossy);
 
         // Figure out the priority.
-        let priority: c_int = match record.level() {
-            Level::Critical => libc::LOG_CRIT,
-            Level::Error => libc::LOG_ERR,
-            Level::Warning => libc::LOG_WARNING,
-            Level::Debug | Level::Trace => libc::LOG_DEBUG,
-
-            // `slog::Level` isn't non-exhaustive, so adding any more levels
-            // would be a breaking change. That is highly unlikely to ever
-            // happen. Still, we'll handle the possibility here, just in case.
-            _ => libc::LOG_INFO
-        };
+        let priority: c_int = self.priority_map.priority_of(record, values);
 
         // All set. Submit the log message.
         unsafe {
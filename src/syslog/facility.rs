@@ -13,7 +13,7 @@ use libc::c_int;
 /// `enum` are available on all platforms, and variants not present on the
 /// target platform will be mapped to a reasonable alternative.
 #[allow(missing_docs)]
-#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
 #[non_exhaustive]
 #[serde(rename_all = "lowercase")]
 pub enum Facility {
@@ -110,6 +110,7 @@ pub enum Facility {
     Security,
 
     Syslog,
+    #[default]
     User,
     Uucp,
 }
@@ -152,12 +153,6 @@ impl Facility {
     }
 }
 
-impl Default for Facility {
-    fn default() -> Self {
-        Facility::User
-    }
-}
-
 impl Display for Facility {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(self.name())
@@ -433,7 +428,7 @@ pub struct UnknownFacilityError {
 impl UnknownFacilityError {
     /// The unrecognized facility name.
     pub fn name(&self) -> &str {
-        &*self.name
+        &self.name
     }
 }
 
@@ -4,6 +4,7 @@
 //! 
 //! [`MsgFormat`]: trait.MsgFormat.html
 
+use crate::{ErrorKind, Result};
 use serde::{Deserialize, Serialize};
 use slog::{KV, OwnedKVList, Record};
 use std::cell::Cell;
@@ -64,24 +65,53 @@ pub trait MsgFormat: Sync + Send + Debug {
             Ok(s)
         }
     }
+
+    /// Formats a log message and its key-value pairs into the STRUCTURED-DATA
+    /// and MSG parts of an [RFC 5424] syslog message.
+    /// 
+    /// This is only used when sending log messages over a socket (see
+    /// [`Transport`]). The POSIX `syslog` function has no place for
+    /// structured data, so it gets only the result of `to_string`.
+    /// 
+    /// The structured data, if any, must consist of complete, properly
+    /// escaped SD-ELEMENTs. The default implementation returns no structured
+    /// data, and the result of `to_string` as the message.
+    /// 
+    /// [RFC 5424]: https://tools.ietf.org/html/rfc5424
+    /// [`Transport`]: ../enum.Transport.html
+    fn to_structured_data_and_msg(&self, record: &Record, values: &OwnedKVList) -> slog::Result<(Option<String>, String)> {
+        Ok((None, MsgFormat::to_string(self, record, values)?))
+    }
 }
 
 impl<T: MsgFormat + ?Sized> MsgFormat for &T {
     fn fmt(&self, f: &mut fmt::Formatter, record: &Record, values: &OwnedKVList) -> slog::Result {
         MsgFormat::fmt(&**self, f, record, values)
     }
+
+    fn to_structured_data_and_msg(&self, record: &Record, values: &OwnedKVList) -> slog::Result<(Option<String>, String)> {
+        MsgFormat::to_structured_data_and_msg(&**self, record, values)
+    }
 }
 
 impl<T: MsgFormat + ?Sized> MsgFormat for Box<T> {
     fn fmt(&self, f: &mut fmt::Formatter, record: &Record, values: &OwnedKVList) -> slog::Result {
         MsgFormat::fmt(&**self, f, record, values)
     }
+
+    fn to_structured_data_and_msg(&self, record: &Record, values: &OwnedKVList) -> slog::Result<(Option<String>, String)> {
+        MsgFormat::to_structured_data_and_msg(&**self, record, values)
+    }
 }
 
 impl<T: MsgFormat + ?Sized> MsgFormat for Arc<T> {
     fn fmt(&self, f: &mut fmt::Formatter, record: &Record, values: &OwnedKVList) -> slog::Result {
         MsgFormat::fmt(&**self, f, record, values)
     }
+
+    fn to_structured_data_and_msg(&self, record: &Record, values: &OwnedKVList) -> slog::Result<(Option<String>, String)> {
+        MsgFormat::to_structured_data_and_msg(&**self, record, values)
+    }
 }
 
 /// An implementation of [`MsgFormat`] that discards the key-value pairs and
@@ -144,7 +174,7 @@ struct Rfc5424LikeValueEscaper<W: fmt::Write>(W);
 
 impl<W: fmt::Write> fmt::Write for Rfc5424LikeValueEscaper<W> {
     fn write_str(&mut self, mut s: &str) -> fmt::Result {
-        while let Some(index) = s.find(|c| c == '\\' || c == '"' || c == ']') {
+        while let Some(index) = s.find(['\\', '"', ']']) {
             if index != 0 {
                 self.0.write_str(&s[..index])?;
             }
@@ -156,7 +186,7 @@ impl<W: fmt::Write> fmt::Write for Rfc5424LikeValueEscaper<W> {
                 s = &s[(index + 1)..];
             }
             else {
-                s = &"";
+                s = "";
                 break;
             }
         }
@@ -194,7 +224,7 @@ fn test_rfc_5424_like_value_escaper() {
 
         {
             let input = format!("{}", c);
-            case(&*input, &*ec);
+            case(&input, &ec);
         }
 
         for at_start_count in 0..=2 {
@@ -205,27 +235,27 @@ fn test_rfc_5424_like_value_escaper() {
             let mut expected_output = String::new();
 
             // Place the symbol(s) at the beginning of the strings.
-            input.extend(iter::repeat(c).take(at_start_count));
-            expected_output.extend(iter::repeat(&*ec).take(at_start_count));
+            input.extend(iter::repeat_n(c, at_start_count));
+            expected_output.extend(iter::repeat_n(&*ec, at_start_count));
 
             // First plain text.
             input.push_str("foo");
             expected_output.push_str("foo");
 
             // Middle symbol(s).
-            input.extend(iter::repeat(c).take(at_mid_count));
-            expected_output.extend(iter::repeat(&*ec).take(at_mid_count));
+            input.extend(iter::repeat_n(c, at_mid_count));
+            expected_output.extend(iter::repeat_n(&*ec, at_mid_count));
 
             // Second plain text.
             input.push_str("bar");
             expected_output.push_str("bar");
 
             // End symbol(s).
-            input.extend(iter::repeat(c).take(at_end_count));
-            expected_output.extend(iter::repeat(&*ec).take(at_end_count));
+            input.extend(iter::repeat_n(c, at_end_count));
+            expected_output.extend(iter::repeat_n(&*ec, at_end_count));
 
             // Finally, test this combination.
-            case(&*input, &*expected_output);
+            case(&input, &expected_output);
         }}}
     }
 
@@ -337,22 +367,214 @@ fn test_default_msg_format() {
     );
 }
 
+/// An implementation of [`MsgFormat`] that formats the key-value pairs of a
+/// log [`Record`] as a conformant [RFC 5424] SD-ELEMENT.
+/// 
+/// All key-value pairs are placed into a single SD-ELEMENT, whose SD-ID is
+/// `name@enterprise_number`. Keys are converted to valid PARAM-NAMEs by
+/// replacing forbidden characters with `_` and truncating them to 32
+/// characters.
+/// 
+/// When sending log messages over a socket (see [`Transport`]), the
+/// SD-ELEMENT goes into the STRUCTURED-DATA part of the message, where
+/// collectors (e.g., rsyslog's `mmpstrucdata`) can parse it. Otherwise, it is
+/// appended to the message, as with [`DefaultMsgFormat`].
+/// 
+/// # Example output
+/// 
+/// Given a log message `Hello, world!`, where the key `key1` has the value
+/// `value1`, the formatted message will be
+/// `Hello, world! [slog@32473 key1="value1"]` with the default SD-ID.
+/// 
+/// # Example
+/// 
+/// ```
+/// use sloggers::Build;
+/// use sloggers::syslog::SyslogBuilder;
+/// use sloggers::syslog::format::StructuredDataMsgFormat;
+/// 
+/// let logger = SyslogBuilder::new()
+///     .format(StructuredDataMsgFormat::new("myapp", 32473).unwrap())
+///     .build()
+///     .unwrap();
+/// ```
+/// 
+/// [`DefaultMsgFormat`]: struct.DefaultMsgFormat.html
+/// [`MsgFormat`]: trait.MsgFormat.html
+/// [`Record`]: https://docs.rs/slog/2/slog/struct.Record.html
+/// [`Transport`]: ../enum.Transport.html
+/// [RFC 5424]: https://tools.ietf.org/html/rfc5424#section-6.3
+#[derive(Clone, Debug)]
+pub struct StructuredDataMsgFormat {
+    sd_id: String,
+}
+
+impl StructuredDataMsgFormat {
+    /// The default SD-ID name, `slog`.
+    pub const DEFAULT_NAME: &'static str = "slog";
+
+    /// The default private enterprise number, `32473`.
+    /// 
+    /// This number is reserved for use in documentation by [RFC 5612].
+    /// Applications should use their organization's own number, as assigned
+    /// by IANA.
+    /// 
+    /// [RFC 5612]: https://tools.ietf.org/html/rfc5612
+    pub const DEFAULT_ENTERPRISE_NUMBER: u32 = 32473;
+
+    /// Makes a new `StructuredDataMsgFormat` whose SD-ID is
+    /// `name@enterprise_number`.
+    /// 
+    /// # Errors
+    /// 
+    /// Fails if `name` is empty or contains characters not allowed in an
+    /// SD-ID (`=`, space, `]`, `"`, `@`, or non-printable or non-ASCII
+    /// characters), or if the resulting SD-ID is longer than 32 characters.
+    pub fn new(name: &str, enterprise_number: u32) -> Result<Self> {
+        track_assert!(!name.is_empty(), ErrorKind::Invalid, "Empty SD-ID name");
+        track_assert!(
+            name.chars().all(|c| is_sd_name_char(c) && c != '@'),
+            ErrorKind::Invalid,
+            "Invalid SD-ID name: {:?}",
+            name
+        );
+
+        let sd_id = format!("{}@{}", name, enterprise_number);
+        track_assert!(sd_id.len() <= 32, ErrorKind::Invalid, "Too long SD-ID: {:?}", sd_id);
+        Ok(StructuredDataMsgFormat { sd_id })
+    }
+
+    /// The SD-ID of the SD-ELEMENTs generated by this format.
+    pub fn sd_id(&self) -> &str {
+        &self.sd_id
+    }
+
+    /// Formats the key-value pairs as an SD-ELEMENT, or returns `None` if
+    /// there are no key-value pairs.
+    fn structured_data(&self, record: &Record, values: &OwnedKVList) -> slog::Result<Option<String>> {
+        struct SerializerImpl {
+            sd: String,
+        }
+
+        impl slog::Serializer for SerializerImpl {
+            fn emit_arguments(&mut self, key: slog::Key, val: &fmt::Arguments) -> slog::Result {
+                use fmt::Write;
+
+                // PARAM-NAMEs can't be escaped, so forbidden characters are
+                // replaced instead.
+                self.sd.push(' ');
+                if key.is_empty() {
+                    self.sd.push('_');
+                }
+                self.sd.extend(key.chars().take(32).map(|c| if is_sd_name_char(c) { c } else { '_' }));
+
+                self.sd.push_str("=\"");
+                write!(Rfc5424LikeValueEscaper(&mut self.sd), "{}", val)?;
+                self.sd.push('"');
+                Ok(())
+            }
+        }
+
+        let mut serializer = SerializerImpl { sd: format!("[{}", self.sd_id) };
+        record.kv().serialize(record, &mut serializer)?;
+        values.serialize(record, &mut serializer)?;
+
+        if serializer.sd.len() == self.sd_id.len() + 1 {
+            Ok(None)
+        }
+        else {
+            serializer.sd.push(']');
+            Ok(Some(serializer.sd))
+        }
+    }
+}
+
+impl Default for StructuredDataMsgFormat {
+    fn default() -> Self {
+        StructuredDataMsgFormat {
+            sd_id: format!("{}@{}", Self::DEFAULT_NAME, Self::DEFAULT_ENTERPRISE_NUMBER),
+        }
+    }
+}
+
+impl MsgFormat for StructuredDataMsgFormat {
+    fn fmt(&self, f: &mut fmt::Formatter, record: &Record, values: &OwnedKVList) -> slog::Result {
+        write!(f, "{}", record.msg())?;
+        if let Some(sd) = self.structured_data(record, values)? {
+            write!(f, " {}", sd)?;
+        }
+        Ok(())
+    }
+
+    fn to_structured_data_and_msg(&self, record: &Record, values: &OwnedKVList) -> slog::Result<(Option<String>, String)> {
+        Ok((self.structured_data(record, values)?, record.msg().to_string()))
+    }
+}
+
+/// Checks if a character is allowed in an SD-NAME (that is, an SD-ID or a
+/// PARAM-NAME).
+fn is_sd_name_char(c: char) -> bool {
+    c.is_ascii_graphic() && c != '=' && c != ']' && c != '"'
+}
+
+#[test]
+fn test_structured_data_msg_format() {
+    use slog::Level;
+
+    let format = StructuredDataMsgFormat::new("myapp", 12345).unwrap();
+
+    // `record!` borrows temporaries, so the record is only available inside
+    // this closure.
+    let with_record = |kv: bool, f: &dyn Fn(&Record)| {
+        if kv {
+            f(&record!(Level::Info, "", &format_args!("Hello, world!"), b!("key 1" => "a \"quoted\" [value]")))
+        }
+        else {
+            f(&record!(Level::Info, "", &format_args!("Hello, world!"), b!()))
+        }
+    };
+
+    with_record(true, &|record| {
+        assert_eq!(
+            format.to_string(record, &o!().into()).unwrap(),
+            r#"Hello, world! [myapp@12345 key_1="a \"quoted\" [value\]"]"#
+        );
+        assert_eq!(
+            format.to_structured_data_and_msg(record, &o!("key2" => 2).into()).unwrap(),
+            (Some(r#"[myapp@12345 key_1="a \"quoted\" [value\]" key2="2"]"#.to_owned()), "Hello, world!".to_owned())
+        );
+    });
+
+    with_record(false, &|record| {
+        assert_eq!(format.to_string(record, &o!().into()).unwrap(), "Hello, world!");
+        assert_eq!(format.to_structured_data_and_msg(record, &o!().into()).unwrap(), (None, "Hello, world!".to_owned()));
+    });
+
+    assert_eq!(StructuredDataMsgFormat::default().sd_id(), "slog@32473");
+    assert!(StructuredDataMsgFormat::new("", 1).is_err());
+    assert!(StructuredDataMsgFormat::new("my app", 1).is_err());
+    assert!(StructuredDataMsgFormat::new("a@b", 1).is_err());
+    assert!(StructuredDataMsgFormat::new(&"a".repeat(30), 12345).is_err());
+}
+
 /// Enumeration of built-in `MsgFormat`s, for use with serde.
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 #[non_exhaustive]
 #[serde(rename_all = "snake_case")]
 pub enum MsgFormatConfig {
     /// [`DefaultMsgFormat`](struct.DefaultMsgFormat.html).
+    #[default]
     Default,
 
     /// [`BasicMsgFormat`](struct.BasicMsgFormat.html).
     Basic,
-}
 
-impl Default for MsgFormatConfig {
-    fn default() -> Self {
-        MsgFormatConfig::Default
-    }
+    /// [`StructuredDataMsgFormat`](struct.StructuredDataMsgFormat.html),
+    /// with the default SD-ID.
+    /// 
+    /// In `SyslogConfig`, the SD-ID can be changed with the `sd_name` and
+    /// `enterprise_number` settings.
+    StructuredData,
 }
 
 impl From<MsgFormatConfig> for Arc<dyn MsgFormat> {
@@ -366,6 +588,7 @@ impl From<&MsgFormatConfig> for Arc<dyn MsgFormat> {
         match *conf {
             MsgFormatConfig::Default => Arc::new(DefaultMsgFormat),
             MsgFormatConfig::Basic => Arc::new(BasicMsgFormat),
+            MsgFormatConfig::StructuredData => Arc::new(StructuredDataMsgFormat::default()),
         }
     }
 }
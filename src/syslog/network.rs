@@ -0,0 +1,210 @@
+use crate::Result;
+use chrono::{Local, SecondsFormat, Utc};
+use libc::c_int;
+use slog::{Drain, Record, OwnedKVList};
+use std::env;
+use std::process;
+use std::result::Result as StdResult;
+use std::sync::Arc;
+use super::format::MsgFormat;
+use super::transport::{Connection, Protocol};
+use super::{PriorityMap, SyslogBuilder};
+
+/// A drain that sends [RFC 5424] or [RFC 3164] messages over a socket, as
+/// opposed to calling the libc `syslog` function like `SyslogDrain` does.
+/// 
+/// [RFC 5424]: https://tools.ietf.org/html/rfc5424
+/// [RFC 3164]: https://tools.ietf.org/html/rfc3164
+pub(super) struct NetworkDrain {
+    connection: Connection,
+    protocol: Protocol,
+    facility: c_int,
+    format: Arc<dyn MsgFormat>,
+    priority_map: PriorityMap,
+    max_message_len: Option<usize>,
+
+    /// The header fields after the timestamp, already joined with spaces,
+    /// since they are the same for every message.
+    /// 
+    /// For RFC 5424, these are the HOSTNAME, APP-NAME, PROCID, and MSGID
+    /// fields. For RFC 3164, these are the HOSTNAME and TAG fields.
+    header: String,
+}
+
+impl NetworkDrain {
+    pub fn new(builder: &SyslogBuilder) -> Result<Self> {
+        let connection = track!(Connection::open(&builder.transport, builder.protocol))?;
+
+        let hostname = match &builder.hostname {
+            Some(hostname) => hostname.clone(),
+            None => hostname::get().map(|h| h.to_string_lossy().into_owned()).unwrap_or_default(),
+        };
+
+        let app_name = match &builder.ident {
+            Some(ident) => ident.to_string_lossy().into_owned(),
+            None => env::current_exe().ok()
+                .and_then(|exe| exe.file_name().map(|name| name.to_string_lossy().into_owned()))
+                .unwrap_or_default(),
+        };
+
+        let procid = if builder.option & libc::LOG_PID != 0 {
+            process::id().to_string()
+        }
+        else {
+            String::new()
+        };
+
+        let header = match builder.protocol {
+            Protocol::Rfc5424 => format!(
+                "{} {} {} {}",
+                header_field(&hostname, 255),
+                header_field(&app_name, 48),
+                header_field(&procid, 128),
+                header_field(builder.msgid.as_deref().unwrap_or_default(), 32),
+            ),
+            Protocol::Rfc3164 => {
+                // The TAG is terminated by the first non-alphanumeric
+                // character, but in practice, collectors accept any
+                // printable characters other than `:` and `[`.
+                let tag: String = header_field(&app_name, 32)
+                    .chars()
+                    .map(|c| if c == ':' || c == '[' { '_' } else { c })
+                    .collect();
+
+                if procid.is_empty() {
+                    format!("{} {}:", header_field(&hostname, 255), tag)
+                }
+                else {
+                    format!("{} {}[{}]:", header_field(&hostname, 255), tag, procid)
+                }
+            }
+        };
+
+        let max_message_len = match (builder.max_message_len, builder.protocol) {
+            (Some(len), _) => Some(len),
+            (None, Protocol::Rfc3164) => Some(1024),
+            (None, Protocol::Rfc5424) => None,
+        };
+
+        Ok(NetworkDrain {
+            connection,
+            protocol: builder.protocol,
+            facility: builder.facility.into(),
+            format: builder.format.clone(),
+            priority_map: builder.priority_map.clone(),
+            max_message_len,
+            header,
+        })
+    }
+
+    /// Formats a complete message, truncated to the maximum length.
+    fn format_message(&self, priority: c_int, structured_data: Option<&str>, msg: &str) -> String {
+        let mut message = match self.protocol {
+            Protocol::Rfc5424 => format!(
+                "<{}>1 {} {} {} {}",
+                self.facility | priority,
+                Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true),
+                self.header,
+                structured_data.unwrap_or("-"),
+                msg,
+            ),
+
+            // RFC 3164 messages are a single line, so line breaks in the
+            // message are replaced with spaces.
+            Protocol::Rfc3164 => format!(
+                "<{}>{} {} {}",
+                self.facility | priority,
+                Local::now().format("%b %e %H:%M:%S"),
+                self.header,
+                msg.replace(['\r', '\n'], " "),
+            ),
+        };
+
+        if let Some(max_len) = self.max_message_len {
+            truncate(&mut message, max_len);
+        }
+        message
+    }
+}
+
+impl Drain for NetworkDrain {
+    type Ok = ();
+    type Err = slog::Never;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> StdResult<Self::Ok, Self::Err> {
+        // As in `SyslogDrain`, if formatting fails, log only the message, and
+        // then log the error separately.
+        let ((structured_data, msg), fmt_err) = match MsgFormat::to_structured_data_and_msg(self.format.as_ref(), record, values) {
+            Ok(parts) => (parts, None),
+            Err(fmt_err) => ((None, record.msg().to_string()), Some(fmt_err.to_string())),
+        };
+
+        // Errors are ignored, because there is nowhere to report them. The
+        // connection will be retried with the next message.
+        let message = self.format_message(self.priority_map.priority_of(record, values), structured_data.as_deref(), &msg);
+        let _ = self.connection.send(message.as_bytes());
+
+        if let Some(fmt_err) = fmt_err {
+            let message = self.format_message(
+                libc::LOG_ERR,
+                None,
+                &format!("Error fully formatting the previous log message: {}", fmt_err),
+            );
+            let _ = self.connection.send(message.as_bytes());
+        }
+
+        Ok(())
+    }
+}
+
+/// Makes a header field valid for RFC 5424.
+/// 
+/// Header fields consist of printable ASCII characters only, and have a
+/// maximum length. Other characters are replaced with `_`, and empty fields
+/// become the NILVALUE `-`.
+fn header_field(value: &str, max_len: usize) -> String {
+    if value.is_empty() {
+        return "-".to_owned();
+    }
+
+    value.chars()
+        .take(max_len)
+        .map(|c| if c.is_ascii_graphic() { c } else { '_' })
+        .collect()
+}
+
+/// Truncates `s` to at most `max_len` bytes, without splitting a character.
+fn truncate(s: &mut String, max_len: usize) {
+    if s.len() <= max_len {
+        return;
+    }
+
+    let mut len = max_len;
+    while !s.is_char_boundary(len) {
+        len -= 1;
+    }
+    s.truncate(len);
+}
+
+#[test]
+fn test_truncate() {
+    fn case(input: &str, max_len: usize, expected_output: &str) {
+        let mut s = input.to_owned();
+        truncate(&mut s, max_len);
+        assert_eq!(s, expected_output);
+    }
+
+    case("hello", 10, "hello");
+    case("hello", 5, "hello");
+    case("hello", 3, "hel");
+    case("h\u{e9}llo", 2, "h");
+    case("h\u{e9}llo", 3, "h\u{e9}");
+}
+
+#[test]
+fn test_header_field() {
+    assert_eq!(header_field("", 48), "-");
+    assert_eq!(header_field("my-app", 48), "my-app");
+    assert_eq!(header_field("my app\u{e9}", 48), "my_app_");
+    assert_eq!(header_field("abcdef", 4), "abcd");
+}
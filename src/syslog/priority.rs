@@ -0,0 +1,256 @@
+use crate::{Error, ErrorKind, Result};
+use libc::c_int;
+use serde::{Deserialize, Serialize};
+use slog::{Level, OwnedKVList, Record, KV};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// The key of a key-value pair that overrides the syslog priority of a log
+/// record.
+/// 
+/// The value must be the name of a [`Priority`] (e.g., `alert`). If it is
+/// not, the pair is ignored and the priority is taken from the
+/// [`PriorityMap`] as usual. The pair is still formatted into the message
+/// like any other key-value pair.
+/// 
+/// The pair can also be attached to a logger (e.g., a child logger of a
+/// subsystem that pages someone), in which case it applies to all records
+/// of that logger. A pair of the record itself takes precedence over the
+/// logger's.
+/// 
+/// # Example
+/// 
+/// ```
+/// use slog::crit;
+/// use sloggers::Build;
+/// use sloggers::syslog::SyslogBuilder;
+/// 
+/// let logger = SyslogBuilder::new().build().unwrap();
+/// crit!(logger, "Disk is on fire"; "syslog_priority" => "emerg");
+/// ```
+/// 
+/// [`Priority`]: enum.Priority.html
+/// [`PriorityMap`]: struct.PriorityMap.html
+pub const PRIORITY_KEY: &str = "syslog_priority";
+
+/// A syslog priority (also called severity). Conversions are provided to
+/// `c_int`.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Emerg,
+    Alert,
+    Crit,
+    Err,
+    Warning,
+    Notice,
+    Info,
+    Debug,
+}
+
+impl Priority {
+    /// Gets the name of this `Priority`, in lowercase.
+    /// 
+    /// The `FromStr` implementation accepts the same names, but it is
+    /// case-insensitive.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Priority::Emerg   => "emerg",
+            Priority::Alert   => "alert",
+            Priority::Crit    => "crit",
+            Priority::Err     => "err",
+            Priority::Warning => "warning",
+            Priority::Notice  => "notice",
+            Priority::Info    => "info",
+            Priority::Debug   => "debug",
+        }
+    }
+}
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl From<Priority> for c_int {
+    fn from(priority: Priority) -> Self {
+        match priority {
+            Priority::Emerg   => libc::LOG_EMERG,
+            Priority::Alert   => libc::LOG_ALERT,
+            Priority::Crit    => libc::LOG_CRIT,
+            Priority::Err     => libc::LOG_ERR,
+            Priority::Warning => libc::LOG_WARNING,
+            Priority::Notice  => libc::LOG_NOTICE,
+            Priority::Info    => libc::LOG_INFO,
+            Priority::Debug   => libc::LOG_DEBUG,
+        }
+    }
+}
+
+impl FromStr for Priority {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match &*s.to_ascii_lowercase() {
+            "emerg"   => Ok(Priority::Emerg),
+            "alert"   => Ok(Priority::Alert),
+            "crit"    => Ok(Priority::Crit),
+            "err"     => Ok(Priority::Err),
+            "warning" => Ok(Priority::Warning),
+            "notice"  => Ok(Priority::Notice),
+            "info"    => Ok(Priority::Info),
+            "debug"   => Ok(Priority::Debug),
+            _ => track_panic!(ErrorKind::Invalid, "Undefined syslog priority: {:?}", s),
+        }
+    }
+}
+
+/// The syslog priority of log records of each level.
+/// 
+/// The priority of an individual record can be overridden with the
+/// [`PRIORITY_KEY`] key.
+/// 
+/// # Example
+/// 
+/// In a configuration file:
+/// 
+/// ```
+/// use sloggers::syslog::{Priority, SyslogConfig};
+/// 
+/// let toml = r#"
+/// [priority_map]
+/// critical = "alert"
+/// info = "notice"
+/// "#;
+/// let config: SyslogConfig = serdeconv::from_toml_str(toml).unwrap();
+/// assert_eq!(config.priority_map.critical, Priority::Alert);
+/// assert_eq!(config.priority_map.error, Priority::Err);
+/// ```
+/// 
+/// [`PRIORITY_KEY`]: constant.PRIORITY_KEY.html
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[non_exhaustive]
+#[serde(default)]
+pub struct PriorityMap {
+    /// Priority of `critical` records. The default is `crit`.
+    pub critical: Priority,
+
+    /// Priority of `error` records. The default is `err`.
+    pub error: Priority,
+
+    /// Priority of `warning` records. The default is `warning`.
+    pub warning: Priority,
+
+    /// Priority of `info` records. The default is `info`.
+    pub info: Priority,
+
+    /// Priority of `debug` records. The default is `debug`.
+    pub debug: Priority,
+
+    /// Priority of `trace` records. The default is `debug`.
+    pub trace: Priority,
+}
+
+impl PriorityMap {
+    /// Creates a new `PriorityMap` with the default mapping.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Gets the priority of log records of the given level.
+    pub fn get(&self, level: Level) -> Priority {
+        match level {
+            Level::Critical => self.critical,
+            Level::Error => self.error,
+            Level::Warning => self.warning,
+            Level::Info => self.info,
+            Level::Debug => self.debug,
+            Level::Trace => self.trace,
+        }
+    }
+
+    /// Gets the priority of a log record, taking the [`PRIORITY_KEY`] key
+    /// into account.
+    /// 
+    /// [`PRIORITY_KEY`]: constant.PRIORITY_KEY.html
+    pub(super) fn priority_of(&self, record: &Record, values: &OwnedKVList) -> c_int {
+        struct SerializerImpl(Option<Priority>);
+
+        impl slog::Serializer for SerializerImpl {
+            fn emit_arguments(&mut self, key: slog::Key, val: &fmt::Arguments) -> slog::Result {
+                if key == PRIORITY_KEY {
+                    if let Ok(priority) = val.to_string().parse() {
+                        self.0 = Some(priority);
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        // The record's own key-value pairs take precedence over the
+        // logger's, which are only serialized if the record has no override.
+        let mut serializer = SerializerImpl(None);
+        let _ = record.kv().serialize(record, &mut serializer);
+        if serializer.0.is_none() {
+            let _ = values.serialize(record, &mut serializer);
+        }
+
+        serializer.0.unwrap_or_else(|| self.get(record.level())).into()
+    }
+}
+
+impl Default for PriorityMap {
+    fn default() -> Self {
+        PriorityMap {
+            critical: Priority::Crit,
+            error: Priority::Err,
+            warning: Priority::Warning,
+            info: Priority::Info,
+            debug: Priority::Debug,
+            trace: Priority::Debug,
+        }
+    }
+}
+
+#[test]
+fn test_priority_from_str() {
+    assert_eq!(Priority::from_str("alert").unwrap(), Priority::Alert);
+    assert_eq!(Priority::from_str("NOTICE").unwrap(), Priority::Notice);
+    assert!(Priority::from_str("critical").is_err());
+}
+
+#[test]
+fn test_priority_of() {
+    let mut map = PriorityMap::new();
+    map.info = Priority::Notice;
+
+    assert_eq!(
+        map.priority_of(&record!(Level::Info, "", &format_args!(""), b!()), &o!().into()),
+        libc::LOG_NOTICE
+    );
+    assert_eq!(
+        map.priority_of(&record!(Level::Trace, "", &format_args!(""), b!()), &o!().into()),
+        libc::LOG_DEBUG
+    );
+    assert_eq!(
+        map.priority_of(&record!(Level::Info, "", &format_args!(""), b!("syslog_priority" => "alert")), &o!().into()),
+        libc::LOG_ALERT
+    );
+    assert_eq!(
+        map.priority_of(&record!(Level::Info, "", &format_args!(""), b!()), &o!("syslog_priority" => "alert").into()),
+        libc::LOG_ALERT
+    );
+    assert_eq!(
+        map.priority_of(
+            &record!(Level::Info, "", &format_args!(""), b!("syslog_priority" => "emerg")),
+            &o!("syslog_priority" => "alert").into()
+        ),
+        libc::LOG_EMERG
+    );
+    assert_eq!(
+        map.priority_of(&record!(Level::Info, "", &format_args!(""), b!("syslog_priority" => "bogus")), &o!().into()),
+        libc::LOG_NOTICE
+    );
+}
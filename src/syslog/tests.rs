@@ -1,9 +1,15 @@
-use crate::Build;
-use crate::syslog::{Facility, mock, SyslogBuilder};
+use crate::{Build, Config};
+use crate::syslog::{Facility, mock, Protocol, SyslogBuilder, SyslogConfig, Transport};
 use crate::syslog::format::CustomMsgFormat;
+use crate::syslog::transport::Connection;
 use crate::types::{Severity, SourceLocation};
-use slog::{debug, info};
+use slog::{debug, info, warn};
 use std::ffi::CStr;
+use std::io::{self, Read};
+use std::net::{TcpListener, UdpSocket};
+use std::os::unix::net::UnixDatagram;
+use std::time::{Duration, Instant};
+use tempfile::Builder as TempDirBuilder;
 
 #[test]
 fn test_log() {
@@ -102,3 +108,282 @@ fn test_log() {
 
     assert!(events == expected_events, "events didn't match\ngot: {:#?}\nexpected: {:#?}", events, expected_events);
 }
+
+/// Checks an RFC 5424 message, ignoring the timestamp.
+fn assert_rfc5424(msg: &str, expected_pri: &str, expected_rest: &str) {
+    let mut fields = msg.splitn(3, ' ');
+    assert_eq!(fields.next(), Some(expected_pri), "msg: {:?}", msg);
+    assert!(fields.next().unwrap().ends_with('Z'), "msg: {:?}", msg);
+    assert_eq!(fields.next(), Some(expected_rest), "msg: {:?}", msg);
+}
+
+#[test]
+fn test_udp_transport() {
+    let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+    server.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+    let (logger, guard) = SyslogBuilder::new()
+        .transport(Transport::Udp(server.local_addr().unwrap().to_string()))
+        .facility(Facility::Local0)
+        .ident_str("my-app")
+        .hostname("my-host")
+        .msgid("audit")
+        .source_location(SourceLocation::None)
+        .build_with_guard()
+        .unwrap();
+    warn!(logger, "Hello"; "key" => "value");
+    guard.flush();
+
+    let mut buf = [0; 1024];
+    let size = server.recv(&mut buf).unwrap();
+    assert_rfc5424(
+        std::str::from_utf8(&buf[..size]).unwrap(),
+        "<132>1",
+        "my-host my-app - audit - Hello [key=\"value\"]",
+    );
+}
+
+#[test]
+fn test_priority_map() {
+    let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+    server.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+    let config: SyslogConfig = serdeconv::from_toml_str(&format!(
+        r#"
+transport = "udp://{}"
+facility = "local0"
+hostname = "my-host"
+ident = "my-app"
+source_location = "none"
+
+[priority_map]
+info = "notice"
+"#,
+        server.local_addr().unwrap()
+    )).unwrap();
+    let (logger, guard) = config.try_to_builder().unwrap().build_with_guard().unwrap();
+    info!(logger, "foo");
+    info!(logger, "bar"; "syslog_priority" => "alert");
+    guard.flush();
+
+    let mut buf = [0; 1024];
+    let size = server.recv(&mut buf).unwrap();
+    assert_rfc5424(std::str::from_utf8(&buf[..size]).unwrap(), "<133>1", "my-host my-app - - - foo");
+    let size = server.recv(&mut buf).unwrap();
+    assert_rfc5424(
+        std::str::from_utf8(&buf[..size]).unwrap(),
+        "<129>1",
+        "my-host my-app - - - bar [syslog_priority=\"alert\"]",
+    );
+}
+
+#[test]
+fn test_tcp_transport() {
+    let server = TcpListener::bind("127.0.0.1:0").unwrap();
+
+    let config: SyslogConfig = serdeconv::from_toml_str(&format!(
+        r#"
+transport = "tcp://{}"
+hostname = "my host"
+ident = "my-app"
+log_pid = true
+source_location = "none"
+"#,
+        server.local_addr().unwrap()
+    )).unwrap();
+    let (logger, guard) = config.try_to_builder().unwrap().build_with_guard().unwrap();
+    info!(logger, "foo");
+    info!(logger, "bar baz");
+    guard.flush();
+
+    let (mut stream, _) = server.accept().unwrap();
+    let mut received = String::new();
+    stream.read_to_string(&mut received).unwrap();
+
+    // Each frame is prefixed with its length in octets.
+    let mut frames = Vec::new();
+    let mut rest = received.as_str();
+    while !rest.is_empty() {
+        let (len, frame) = rest.split_at(rest.find(' ').unwrap());
+        let len: usize = len.parse().unwrap();
+        frames.push(&frame[1..=len]);
+        rest = &frame[len + 1..];
+    }
+
+    let header = format!("my_host my-app {} -", std::process::id());
+    assert_eq!(frames.len(), 2);
+    assert_rfc5424(frames[0], "<14>1", &format!("{} - foo", header));
+    assert_rfc5424(frames[1], "<14>1", &format!("{} - bar baz", header));
+}
+
+#[test]
+fn test_tcp_transport_reconnects_with_backoff() {
+    // Connecting to port 0 always fails, but opening the connection succeeds,
+    // because it connects lazily.
+    let mut connection = Connection::open(&Transport::Tcp("127.0.0.1:0".to_owned()), Protocol::Rfc5424).unwrap();
+    let start = Instant::now();
+    let secs = |secs: u64| start + Duration::from_secs(secs);
+
+    // The delay before reconnecting starts at one second, and doubles after each failure.
+    assert_ne!(connection.send_at(b"foo", start).unwrap_err().kind(), io::ErrorKind::NotConnected);
+    assert_eq!(connection.send_at(b"foo", start + Duration::from_millis(500)).unwrap_err().kind(), io::ErrorKind::NotConnected);
+    assert_ne!(connection.send_at(b"foo", secs(1)).unwrap_err().kind(), io::ErrorKind::NotConnected);
+
+    let server = TcpListener::bind("127.0.0.1:0").unwrap();
+    if let Connection::Tcp { addr, .. } = &mut connection {
+        *addr = server.local_addr().unwrap().to_string();
+    }
+    assert_eq!(connection.send_at(b"bar", secs(2)).unwrap_err().kind(), io::ErrorKind::NotConnected);
+    connection.send_at(b"baz", secs(3)).unwrap();
+    connection.send_at(b"qux", secs(3)).unwrap();
+    drop(connection);
+
+    let (mut stream, _) = server.accept().unwrap();
+    let mut received = String::new();
+    stream.read_to_string(&mut received).unwrap();
+    assert_eq!(received, "3 baz3 qux");
+}
+
+#[test]
+fn test_unix_transport() {
+    let dir = TempDirBuilder::new()
+        .prefix("sloggers_test")
+        .tempdir()
+        .expect("Cannot create a temporary directory");
+    let path = dir.path().join("log.sock");
+    let server = UnixDatagram::bind(&path).unwrap();
+    server.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+    let (logger, guard) = SyslogBuilder::new()
+        .transport(Transport::Unix(path))
+        .ident_str("my-app")
+        .hostname("my-host")
+        .format(CustomMsgFormat(|_, _, _| Err(slog::Error::Other)))
+        .source_location(SourceLocation::None)
+        .build_with_guard()
+        .unwrap();
+    info!(logger, "Hello");
+    guard.flush();
+
+    let mut buf = [0; 1024];
+    let size = server.recv(&mut buf).unwrap();
+    assert_rfc5424(std::str::from_utf8(&buf[..size]).unwrap(), "<14>1", "my-host my-app - - - Hello");
+
+    let size = server.recv(&mut buf).unwrap();
+    assert_rfc5424(
+        std::str::from_utf8(&buf[..size]).unwrap(),
+        "<11>1",
+        &format!("my-host my-app - - - Error fully formatting the previous log message: {}", slog::Error::Other),
+    );
+}
+
+#[test]
+fn test_rfc3164_protocol() {
+    let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+    server.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+    let (logger, guard) = SyslogBuilder::new()
+        .transport(Transport::Udp(server.local_addr().unwrap().to_string()))
+        .protocol(Protocol::Rfc3164)
+        .facility(Facility::Local0)
+        .ident_str("my-app")
+        .hostname("my-host")
+        .log_pid()
+        .source_location(SourceLocation::None)
+        .build_with_guard()
+        .unwrap();
+    warn!(logger, "Hello\nworld"; "key" => "value");
+    info!(logger, "{}", "x".repeat(2000));
+    guard.flush();
+
+    let mut buf = [0; 4096];
+    let size = server.recv(&mut buf).unwrap();
+    let msg = std::str::from_utf8(&buf[..size]).unwrap();
+
+    // <PRI>Mmm dd hh:mm:ss HOSTNAME TAG[PID]: MSG
+    assert!(msg.starts_with("<132>"), "msg: {:?}", msg);
+    let timestamp = &msg["<132>".len().."<132>Mmm dd hh:mm:ss".len()];
+    assert!(chrono::NaiveTime::parse_from_str(&timestamp[7..], "%H:%M:%S").is_ok(), "msg: {:?}", msg);
+    assert_eq!(
+        &msg["<132>Mmm dd hh:mm:ss".len()..],
+        format!(" my-host my-app[{}]: Hello world [key=\"value\"]", std::process::id())
+    );
+
+    // Messages are truncated to 1024 bytes by default.
+    let size = server.recv(&mut buf).unwrap();
+    assert_eq!(size, 1024);
+    assert!(buf[..size].ends_with(b"xxx"));
+}
+
+#[test]
+fn test_rfc3164_protocol_over_tcp() {
+    let server = TcpListener::bind("127.0.0.1:0").unwrap();
+
+    let (logger, guard) = SyslogBuilder::new()
+        .transport(Transport::Tcp(server.local_addr().unwrap().to_string()))
+        .protocol(Protocol::Rfc3164)
+        .max_message_len(40)
+        .ident_str("my-app")
+        .hostname("my-host")
+        .source_location(SourceLocation::None)
+        .build_with_guard()
+        .unwrap();
+    info!(logger, "foo");
+    info!(logger, "{}", "x".repeat(100));
+    guard.flush();
+
+    let (mut stream, _) = server.accept().unwrap();
+    let mut received = String::new();
+    stream.read_to_string(&mut received).unwrap();
+
+    // Each message is terminated by a line feed.
+    let lines: Vec<&str> = received.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("<14>"));
+    assert!(lines[0].ends_with(" my-host my-app: foo"));
+    assert_eq!(lines[1].len(), 40);
+}
+
+#[test]
+fn test_structured_data_format() {
+    let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+    server.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+    let config: SyslogConfig = serdeconv::from_toml_str(&format!(
+        r#"
+transport = "udp://{}"
+format = "structured_data"
+sd_name = "myapp"
+enterprise_number = 12345
+hostname = "my-host"
+ident = "my-app"
+source_location = "none"
+"#,
+        server.local_addr().unwrap()
+    )).unwrap();
+    let (logger, guard) = config.try_to_builder().unwrap().build_with_guard().unwrap();
+    info!(logger, "Hello"; "key" => "value");
+    guard.flush();
+
+    let mut buf = [0; 1024];
+    let size = server.recv(&mut buf).unwrap();
+    assert_rfc5424(
+        std::str::from_utf8(&buf[..size]).unwrap(),
+        "<14>1",
+        "my-host my-app - - [myapp@12345 key=\"value\"] Hello",
+    );
+
+    let config: SyslogConfig = serdeconv::from_toml_str(r#"
+format = "structured_data"
+sd_name = "my app"
+"#).unwrap();
+    assert!(config.try_to_builder().is_err());
+}
+
+#[test]
+fn test_transport_from_str() {
+    assert_eq!("libc".parse::<Transport>().unwrap(), Transport::Libc);
+    assert_eq!("tcp://[::1]:601".parse::<Transport>().unwrap(), Transport::Tcp("[::1]:601".to_owned()));
+    assert!("http://example.com".parse::<Transport>().is_err());
+    assert_eq!(String::from(Transport::Unix("/dev/log".into())), "unix:///dev/log");
+}
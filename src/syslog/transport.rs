@@ -0,0 +1,337 @@
+use crate::{Error, ErrorKind, Result};
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::convert::TryFrom;
+use std::io::{self, Write};
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
+use std::os::unix::net::UnixDatagram;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// The timeout of connecting to a TCP syslog server.
+const TCP_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The timeout of sending a message to a TCP syslog server.
+const TCP_WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The initial delay before reconnecting to a TCP syslog server after a connection attempt
+/// fails. The delay is doubled after each failure, up to `TCP_MAX_RECONNECT_DELAY`.
+const TCP_MIN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// The maximum delay before reconnecting to a TCP syslog server.
+const TCP_MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
+/// The way log messages are sent to the syslog server.
+/// 
+/// In configuration files, the transport is written as one of the following
+/// strings:
+/// 
+/// * `"libc"`
+/// * `"udp://HOST:PORT"` (e.g., `"udp://127.0.0.1:514"`)
+/// * `"tcp://HOST:PORT"` (e.g., `"tcp://logs.example.com:601"`)
+/// * `"unix://PATH"` (e.g., `"unix:///dev/log"`)
+/// 
+/// # Example
+/// 
+/// ```
+/// use sloggers::syslog::Transport;
+/// 
+/// assert_eq!(Transport::default(), Transport::Libc);
+/// assert_eq!(
+///     "udp://127.0.0.1:514".parse::<Transport>().unwrap(),
+///     Transport::Udp("127.0.0.1:514".to_owned())
+/// );
+/// assert_eq!(
+///     "unix:///dev/log".parse::<Transport>().unwrap(),
+///     Transport::Unix("/dev/log".into())
+/// );
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[non_exhaustive]
+#[serde(try_from = "String", into = "String")]
+pub enum Transport {
+    /// Submits log messages using the POSIX `syslog` function.
+    /// 
+    /// See the [module-level documentation] for the caveats of this transport.
+    /// 
+    /// [module-level documentation]: index.html
+    #[default]
+    Libc,
+
+    /// Sends messages as UDP datagrams to the given address (`HOST:PORT`).
+    /// 
+    /// The format of the messages is determined by [`Protocol`].
+    /// 
+    /// [`Protocol`]: enum.Protocol.html
+    Udp(String),
+
+    /// Sends messages over a TCP connection to the given address
+    /// (`HOST:PORT`), framed as described in [RFC 6587].
+    /// 
+    /// The format of the messages is determined by [`Protocol`].
+    /// 
+    /// The connection is opened when the first message is sent, so building
+    /// the logger succeeds even if the server is down. If the connection is
+    /// lost, it is reopened when the next message is sent. After a failed
+    /// connection attempt, the messages are dropped until the next attempt,
+    /// which is delayed exponentially (from one second up to one minute).
+    /// Connecting and sending time out after five seconds, but resolving the
+    /// host name is not subject to the timeout.
+    /// 
+    /// [`Protocol`]: enum.Protocol.html
+    /// [RFC 6587]: https://tools.ietf.org/html/rfc6587
+    Tcp(String),
+
+    /// Sends messages as datagrams to the Unix domain socket at the given
+    /// path.
+    /// 
+    /// The format of the messages is determined by [`Protocol`].
+    /// 
+    /// [`Protocol`]: enum.Protocol.html
+    Unix(PathBuf),
+}
+
+impl FromStr for Transport {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s == "libc" {
+            Ok(Transport::Libc)
+        }
+        else if let Some(addr) = s.strip_prefix("udp://") {
+            Ok(Transport::Udp(addr.to_owned()))
+        }
+        else if let Some(addr) = s.strip_prefix("tcp://") {
+            Ok(Transport::Tcp(addr.to_owned()))
+        }
+        else if let Some(path) = s.strip_prefix("unix://") {
+            Ok(Transport::Unix(path.into()))
+        }
+        else {
+            track_panic!(ErrorKind::Invalid, "Undefined syslog transport: {:?}", s)
+        }
+    }
+}
+
+impl TryFrom<String> for Transport {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        track!(s.parse())
+    }
+}
+
+impl From<Transport> for String {
+    fn from(transport: Transport) -> String {
+        match transport {
+            Transport::Libc => "libc".to_owned(),
+            Transport::Udp(addr) => format!("udp://{}", addr),
+            Transport::Tcp(addr) => format!("tcp://{}", addr),
+            Transport::Unix(path) => format!("unix://{}", path.display()),
+        }
+    }
+}
+
+/// The format of log messages sent over a socket.
+/// 
+/// This has no effect when using `Transport::Libc`.
+/// 
+/// # Example
+/// 
+/// ```
+/// use sloggers::syslog::Protocol;
+/// 
+/// assert_eq!(Protocol::default(), Protocol::Rfc5424);
+/// assert_eq!("rfc3164".parse::<Protocol>().unwrap(), Protocol::Rfc3164);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[non_exhaustive]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    /// The [RFC 5424] format:
+    /// `<PRI>1 TIMESTAMP HOSTNAME APP-NAME PROCID MSGID STRUCTURED-DATA MSG`.
+    /// 
+    /// Over TCP, messages are framed by octet counting.
+    /// 
+    /// [RFC 5424]: https://tools.ietf.org/html/rfc5424
+    #[default]
+    Rfc5424,
+
+    /// The legacy BSD format described in [RFC 3164]:
+    /// `<PRI>Mmm dd hh:mm:ss HOSTNAME TAG[PID]: MSG`.
+    /// 
+    /// The timestamp is in local time, and structured data is not
+    /// supported. Over TCP, each message is terminated by a line feed.
+    /// 
+    /// Messages are truncated to 1024 bytes by default, as required by the
+    /// RFC. See [`SyslogBuilder::max_message_len`].
+    /// 
+    /// [RFC 3164]: https://tools.ietf.org/html/rfc3164
+    /// [`SyslogBuilder::max_message_len`]: struct.SyslogBuilder.html#method.max_message_len
+    Rfc3164,
+}
+
+impl FromStr for Protocol {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "rfc5424" => Ok(Protocol::Rfc5424),
+            "rfc3164" => Ok(Protocol::Rfc3164),
+            _ => track_panic!(ErrorKind::Invalid, "Undefined syslog protocol: {:?}", s),
+        }
+    }
+}
+
+/// An open socket to a syslog server.
+pub(super) enum Connection {
+    Udp(UdpSocket),
+    Tcp {
+        addr: String,
+        stream: RefCell<Option<TcpStream>>,
+
+        /// Whether to frame messages by octet counting, as opposed to
+        /// terminating them with a line feed.
+        octet_counting: bool,
+
+        /// The earliest time of the next connection attempt.
+        next_connect: Cell<Instant>,
+
+        /// The delay added to `next_connect` when a connection attempt fails.
+        reconnect_delay: Cell<Duration>,
+    },
+    Unix {
+        path: PathBuf,
+        socket: UnixDatagram,
+    },
+}
+
+impl Connection {
+    /// Opens a socket for the given transport, which must not be
+    /// `Transport::Libc`.
+    pub fn open(transport: &Transport, protocol: Protocol) -> Result<Self> {
+        match transport {
+            Transport::Libc => track_panic!(ErrorKind::Invalid, "The libc transport has no socket"),
+            Transport::Udp(addr) => {
+                let addrs = track!(addr.to_socket_addrs().map_err(Error::from), "addr={:?}", addr)?;
+                let mut last_error = None;
+                for addr in addrs {
+                    let local = if addr.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+                    match UdpSocket::bind(local).and_then(|socket| socket.connect(addr).map(|()| socket)) {
+                        Ok(socket) => return Ok(Connection::Udp(socket)),
+                        Err(e) => last_error = Some(e),
+                    }
+                }
+                match last_error {
+                    Some(e) => Err(track!(Error::from(e), "addr={:?}", addr)),
+                    None => track_panic!(ErrorKind::Invalid, "No address found for {:?}", addr),
+                }
+            }
+            Transport::Tcp(addr) => {
+                // The connection is opened lazily by `send`.
+                Ok(Connection::Tcp {
+                    addr: addr.clone(),
+                    stream: RefCell::new(None),
+                    octet_counting: protocol == Protocol::Rfc5424,
+                    next_connect: Cell::new(Instant::now()),
+                    reconnect_delay: Cell::new(TCP_MIN_RECONNECT_DELAY),
+                })
+            }
+            Transport::Unix(path) => {
+                let socket = track!(UnixDatagram::unbound().map_err(Error::from))?;
+                track!(socket.connect(path).map_err(Error::from), "path={:?}", path)?;
+                Ok(Connection::Unix {
+                    path: path.clone(),
+                    socket,
+                })
+            }
+        }
+    }
+
+    /// Sends a single syslog message.
+    /// 
+    /// If sending fails, the connection is reopened and sending is retried
+    /// once. For TCP, the connection is not reopened until the delay after
+    /// the last failed attempt has elapsed.
+    pub fn send(&self, msg: &[u8]) -> io::Result<()> {
+        self.send_at(msg, Instant::now())
+    }
+
+    /// Same as `send`, but `now` is used as the current time for deciding
+    /// whether to reopen a TCP connection.
+    pub fn send_at(&self, msg: &[u8], now: Instant) -> io::Result<()> {
+        match self {
+            Connection::Udp(socket) => socket.send(msg).map(|_| ()),
+            Connection::Tcp { addr, stream, octet_counting, next_connect, reconnect_delay } => {
+                let mut stream = stream.borrow_mut();
+                let frame = if *octet_counting {
+                    let mut frame = format!("{} ", msg.len()).into_bytes();
+                    frame.extend_from_slice(msg);
+                    frame
+                }
+                else {
+                    let mut frame = msg.to_vec();
+                    frame.push(b'\n');
+                    frame
+                };
+
+                if let Some(s) = stream.as_mut() {
+                    if s.write_all(&frame).is_ok() {
+                        return Ok(());
+                    }
+
+                    // The connection is lost, so reconnect and try again.
+                    *stream = None;
+                }
+
+                if now < next_connect.get() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::NotConnected,
+                        "Waiting to reconnect to the syslog server",
+                    ));
+                }
+                match connect_tcp(addr).and_then(|mut s| s.write_all(&frame).map(|()| s)) {
+                    Ok(s) => {
+                        *stream = Some(s);
+                        reconnect_delay.set(TCP_MIN_RECONNECT_DELAY);
+                        Ok(())
+                    }
+                    Err(e) => {
+                        let delay = reconnect_delay.get();
+                        next_connect.set(now + delay);
+                        reconnect_delay.set((delay * 2).min(TCP_MAX_RECONNECT_DELAY));
+                        Err(e)
+                    }
+                }
+            }
+            Connection::Unix { path, socket } => {
+                if socket.send(msg).is_ok() {
+                    return Ok(());
+                }
+
+                // The syslog server may have been restarted, so reconnect and
+                // try again.
+                socket.connect(path)?;
+                socket.send(msg).map(|_| ())
+            }
+        }
+    }
+}
+
+/// Connects to a TCP syslog server, trying each address `addr` resolves to.
+fn connect_tcp(addr: &str) -> io::Result<TcpStream> {
+    let mut last_error = None;
+    for addr in addr.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, TCP_CONNECT_TIMEOUT) {
+            Ok(stream) => {
+                stream.set_write_timeout(Some(TCP_WRITE_TIMEOUT))?;
+                return Ok(stream);
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, format!("No address found for {:?}", addr))
+    }))
+}
@@ -1,15 +1,24 @@
 //! Terminal logger.
 use crate::build::BuilderCommon;
+use crate::logfmt::LogfmtDrain;
 use crate::misc;
 #[cfg(feature = "slog-kvfilter")]
 use crate::types::KVFilterParameters;
-use crate::types::{Format, OverflowStrategy, Severity, SourceLocation, TimeZone};
-use crate::{Build, Config, Result};
+use crate::types::{
+    Format, LevelHandle, ModuleFilter, OverflowStrategy, Severity, SourceLocation, TimeZone,
+};
+use crate::{AsyncGuard, Build, Config, Error, ErrorKind, Result};
 use serde::{Deserialize, Serialize};
-use slog::Logger;
+use slog::{Drain, Level, Logger, OwnedKVList, Record};
 use slog_term::{self, CompactFormat, FullFormat, PlainDecorator, TermDecorator};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::env;
 use std::fmt::Debug;
-use std::io;
+use std::io::{self, Write};
+use std::result;
+use std::str::FromStr;
 
 /// A logger builder which build loggers that output log records to the terminal.
 ///
@@ -20,6 +29,7 @@ pub struct TerminalLoggerBuilder {
     format: Format,
     timezone: TimeZone,
     destination: Destination,
+    color: Color,
 }
 impl TerminalLoggerBuilder {
     /// Makes a new `TerminalLoggerBuilder` instance.
@@ -29,6 +39,7 @@ impl TerminalLoggerBuilder {
             format: Format::default(),
             timezone: TimeZone::default(),
             destination: Destination::default(),
+            color: Color::default(),
         }
     }
 
@@ -62,12 +73,55 @@ impl TerminalLoggerBuilder {
         self
     }
 
+    /// Sets whether to colorize log records.
+    ///
+    /// This is only used by the `full` and `compact` formats.
+    pub fn color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self
+    }
+
     /// Sets the log level of this logger.
     pub fn level(&mut self, severity: Severity) -> &mut Self {
         self.common.level = severity;
         self
     }
 
+    /// Sets the handle for changing the log level of this logger at runtime.
+    ///
+    /// If this is specified, the log level set by [`level`] is ignored and
+    /// the current level of the handle is used instead.
+    ///
+    /// [`level`]: #method.level
+    pub fn level_handle(&mut self, handle: LevelHandle) -> &mut Self {
+        self.common.level_handle = Some(handle);
+        self
+    }
+
+    /// Sets the per-module log level filter of this logger.
+    ///
+    /// If this is specified, the log level set by [`level`] is only used for the modules
+    /// which match no directive of the filter (and only if the filter has no default level).
+    ///
+    /// [`level`]: #method.level
+    pub fn filter(&mut self, filter: ModuleFilter) -> &mut Self {
+        self.common.filter = Some(filter);
+        self
+    }
+
+    /// Adds a static key-value pair which is attached to all log records of this logger.
+    ///
+    /// This is typically used for attaching the service name or the host name.
+    ///
+    /// The value is used as it is. Placeholders like `{hostname}` are only expanded in
+    /// the `context` table of [`TerminalLoggerConfig`].
+    ///
+    /// [`TerminalLoggerConfig`]: ./struct.TerminalLoggerConfig.html#structfield.context
+    pub fn context(&mut self, key: &str, value: &str) -> &mut Self {
+        self.common.context.push((key.to_owned(), value.to_owned()));
+        self
+    }
+
     /// Sets the size of the asynchronous channel of this logger.
     pub fn channel_size(&mut self, channel_size: usize) -> &mut Self {
         self.common.channel_size = channel_size;
@@ -88,26 +142,96 @@ impl Default for TerminalLoggerBuilder {
         Self::new()
     }
 }
-impl Build for TerminalLoggerBuilder {
-    fn build(&self) -> Result<Logger> {
-        let decorator = self.destination.to_decorator();
+impl TerminalLoggerBuilder {
+    fn build_logger(&self, with_guard: bool) -> (Logger, AsyncGuard) {
         let timestamp = misc::timezone_to_timestamp_fn(self.timezone);
-        let logger = match self.format {
+        let timezone = self.timezone;
+        let color = self.color;
+        match self.format {
             Format::Full => {
-                let format = FullFormat::new(decorator).use_custom_timestamp(timestamp);
-                self.common.build_with_drain(format.build())
+                let format = |stream| {
+                    FullFormat::new(Decorator::new(stream, color))
+                        .use_custom_timestamp(timestamp)
+                        .build()
+                };
+                self.build_with_streams(format, format, with_guard)
             }
             Format::Compact => {
-                let format = CompactFormat::new(decorator).use_custom_timestamp(timestamp);
-                self.common.build_with_drain(format.build())
+                let format = |stream| {
+                    CompactFormat::new(Decorator::new(stream, color))
+                        .use_custom_timestamp(timestamp)
+                        .build()
+                };
+                self.build_with_streams(format, format, with_guard)
             }
-        };
+            Format::Json => self.build_with_streams(
+                |_| misc::json_drain(io::stdout(), timezone),
+                |_| misc::json_drain(io::stderr(), timezone),
+                with_guard,
+            ),
+            Format::Logfmt => self.build_with_streams(
+                |_| LogfmtDrain::new(io::stdout(), timezone),
+                |_| LogfmtDrain::new(io::stderr(), timezone),
+                with_guard,
+            ),
+        }
+    }
+
+    /// Builds a logger which outputs log records to the stream(s) specified by `destination`.
+    ///
+    /// `stdout` and `stderr` make drains which output log records to the given stream.
+    fn build_with_streams<F, G, O, E>(
+        &self,
+        stdout: F,
+        stderr: G,
+        with_guard: bool,
+    ) -> (Logger, AsyncGuard)
+    where
+        F: FnOnce(Stream) -> O,
+        G: FnOnce(Stream) -> E,
+        O: Drain<Ok = ()> + Send + 'static,
+        O::Err: Debug,
+        E: Drain<Ok = (), Err = O::Err> + Send + 'static,
+    {
+        match self.destination {
+            Destination::Stdout => self
+                .common
+                .build_with_drain(stdout(Stream::Stdout), with_guard),
+            Destination::Stderr => self
+                .common
+                .build_with_drain(stderr(Stream::Stderr), with_guard),
+            Destination::Split { threshold } => {
+                let drain = SplitDrain {
+                    stdout: stdout(Stream::Stdout),
+                    stderr: stderr(Stream::Stderr),
+                    threshold: threshold.as_level(),
+                };
+                self.common.build_with_drain(drain, with_guard)
+            }
+        }
+    }
+}
+
+impl Build for TerminalLoggerBuilder {
+    fn build(&self) -> Result<Logger> {
+        let (logger, _) = self.build_logger(false);
         Ok(logger)
     }
+
+    fn build_with_guard(&self) -> Result<(Logger, AsyncGuard)> {
+        Ok(self.build_logger(true))
+    }
 }
 
 /// The destination to which log records will be outputted.
 ///
+/// In configuration files, the destination is written as one of the following strings:
+///
+/// - `"stdout"`
+/// - `"stderr"`
+/// - `"split"` (equivalent to `"split=warning"`)
+/// - `"split=SEVERITY"` (e.g., `"split=error"`)
+///
 /// # Examples
 ///
 /// The default value:
@@ -117,40 +241,199 @@ impl Build for TerminalLoggerBuilder {
 ///
 /// assert_eq!(Destination::default(), Destination::Stderr);
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+///
+/// Splitting log records by their severity:
+///
+/// ```
+/// use sloggers::terminal::Destination;
+/// use sloggers::types::Severity;
+///
+/// assert_eq!(
+///     "split".parse::<Destination>().unwrap(),
+///     Destination::Split { threshold: Severity::Warning }
+/// );
+/// assert_eq!(
+///     "split=error".parse::<Destination>().unwrap(),
+///     Destination::Split { threshold: Severity::Error }
+/// );
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+#[non_exhaustive]
 pub enum Destination {
     /// Standard output.
     Stdout,
 
     /// Standard error.
+    #[default]
     Stderr,
+
+    /// Records at or above `threshold` go to the standard error, and the rest to the standard output.
+    Split {
+        /// The minimum severity of the records which go to the standard error.
+        threshold: Severity,
+    },
 }
-impl Default for Destination {
-    fn default() -> Self {
-        Destination::Stderr
+impl FromStr for Destination {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(threshold) = s.strip_prefix("split=") {
+            let threshold = track!(threshold.parse())?;
+            return Ok(Destination::Split { threshold });
+        }
+        match s {
+            "stdout" => Ok(Destination::Stdout),
+            "stderr" => Ok(Destination::Stderr),
+            "split" => Ok(Destination::Split {
+                threshold: Severity::Warning,
+            }),
+            _ => track_panic!(ErrorKind::Invalid, "Undefined destination: {:?}", s),
+        }
     }
 }
-impl Destination {
-    fn to_decorator(self) -> Decorator {
-        let maybe_term_decorator = match self {
-            Destination::Stdout => TermDecorator::new().stdout().try_build(),
-            Destination::Stderr => TermDecorator::new().stderr().try_build(),
-        };
-        maybe_term_decorator
-            .map(Decorator::Term)
-            .unwrap_or_else(|| match self {
-                Destination::Stdout => Decorator::PlainStdout(PlainDecorator::new(io::stdout())),
-                Destination::Stderr => Decorator::PlainStderr(PlainDecorator::new(io::stderr())),
-            })
+impl TryFrom<String> for Destination {
+    type Error = Error;
+    fn try_from(s: String) -> Result<Self> {
+        track!(s.parse())
+    }
+}
+impl From<Destination> for String {
+    fn from(destination: Destination) -> String {
+        match destination {
+            Destination::Stdout => "stdout".to_owned(),
+            Destination::Stderr => "stderr".to_owned(),
+            Destination::Split { threshold } => format!("split={}", threshold.as_str()),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// A drain which sends log records to either of two drains depending on their levels.
+struct SplitDrain<O, E> {
+    stdout: O,
+    stderr: E,
+    threshold: Level,
+}
+impl<O, E> Drain for SplitDrain<O, E>
+where
+    O: Drain<Ok = ()>,
+    E: Drain<Ok = (), Err = O::Err>,
+{
+    type Ok = ();
+    type Err = O::Err;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> result::Result<(), O::Err> {
+        if record.level().is_at_least(self.threshold) {
+            self.stderr.log(record, values)
+        } else {
+            self.stdout.log(record, values)
+        }
+    }
+}
+
+/// Whether to colorize log records.
+///
+/// # Examples
+///
+/// The default value:
+///
+/// ```
+/// use sloggers::terminal::Color;
+///
+/// assert_eq!(Color::default(), Color::Auto);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum Color {
+    /// Colorizes log records only if the destination is a terminal which supports colors.
+    ///
+    /// Colors are disabled if the `NO_COLOR` environment variable is set to a non-empty value,
+    /// and forced if the `CLICOLOR_FORCE` environment variable is set to a value other than `0`.
+    #[default]
+    Auto,
+
+    /// Always colorizes log records using ANSI escape sequences.
+    Always,
+
+    /// Never colorizes log records.
+    Never,
+}
+impl Color {
+    /// Returns `Some(true)` if colors should be forced, `Some(false)` if they should be disabled,
+    /// or `None` if it depends on the terminal.
+    fn resolve(self) -> Option<bool> {
+        match self {
+            Color::Always => Some(true),
+            Color::Never => Some(false),
+            Color::Auto => {
+                let is_set =
+                    |name, off: &str| env::var_os(name).is_some_and(|v| !v.is_empty() && v != off);
+                if is_set("NO_COLOR", "") {
+                    Some(false)
+                } else if is_set("CLICOLOR_FORCE", "0") {
+                    Some(true)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+impl FromStr for Color {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "auto" => Ok(Color::Auto),
+            "always" => Ok(Color::Always),
+            "never" => Ok(Color::Never),
+            _ => track_panic!(ErrorKind::Invalid, "Undefined color: {:?}", s),
+        }
     }
 }
 
 enum Decorator {
     Term(TermDecorator),
+    AnsiStdout(AnsiDecorator<io::Stdout>),
+    AnsiStderr(AnsiDecorator<io::Stderr>),
     PlainStdout(PlainDecorator<io::Stdout>),
     PlainStderr(PlainDecorator<io::Stderr>),
 }
+impl Decorator {
+    fn new(stream: Stream, color: Color) -> Self {
+        match color.resolve() {
+            Some(true) => {
+                return match stream {
+                    Stream::Stdout => Decorator::AnsiStdout(AnsiDecorator::new(io::stdout())),
+                    Stream::Stderr => Decorator::AnsiStderr(AnsiDecorator::new(io::stderr())),
+                };
+            }
+            Some(false) => {
+                return match stream {
+                    Stream::Stdout => Decorator::PlainStdout(PlainDecorator::new(io::stdout())),
+                    Stream::Stderr => Decorator::PlainStderr(PlainDecorator::new(io::stderr())),
+                };
+            }
+            None => {}
+        }
+
+        let maybe_term_decorator = match stream {
+            Stream::Stdout => TermDecorator::new().stdout().try_build(),
+            Stream::Stderr => TermDecorator::new().stderr().try_build(),
+        };
+        maybe_term_decorator
+            .map(Decorator::Term)
+            .unwrap_or_else(|| match stream {
+                Stream::Stdout => Decorator::PlainStdout(PlainDecorator::new(io::stdout())),
+                Stream::Stderr => Decorator::PlainStderr(PlainDecorator::new(io::stderr())),
+            })
+    }
+}
 impl slog_term::Decorator for Decorator {
     fn with_record<F>(
         &self,
@@ -163,12 +446,69 @@ impl slog_term::Decorator for Decorator {
     {
         match *self {
             Decorator::Term(ref d) => d.with_record(record, logger_values, f),
+            Decorator::AnsiStdout(ref d) => d.with_record(record, logger_values, f),
+            Decorator::AnsiStderr(ref d) => d.with_record(record, logger_values, f),
             Decorator::PlainStdout(ref d) => d.with_record(record, logger_values, f),
             Decorator::PlainStderr(ref d) => d.with_record(record, logger_values, f),
         }
     }
 }
 
+/// A decorator which colorizes log records using ANSI escape sequences,
+/// regardless of whether the output is a terminal.
+struct AnsiDecorator<W: io::Write>(RefCell<W>);
+impl<W: io::Write> AnsiDecorator<W> {
+    fn new(io: W) -> Self {
+        AnsiDecorator(RefCell::new(io))
+    }
+}
+impl<W: io::Write> slog_term::Decorator for AnsiDecorator<W> {
+    fn with_record<F>(&self, record: &Record, _: &OwnedKVList, f: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut dyn slog_term::RecordDecorator) -> io::Result<()>,
+    {
+        let mut io = self.0.borrow_mut();
+        let mut decorator = AnsiRecordDecorator {
+            io: &mut *io,
+            level: record.level(),
+        };
+        f(&mut decorator)?;
+        decorator.flush()
+    }
+}
+
+struct AnsiRecordDecorator<'a, W: io::Write> {
+    io: &'a mut W,
+    level: Level,
+}
+impl<'a, W: io::Write> io::Write for AnsiRecordDecorator<'a, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.io.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.io.flush()
+    }
+}
+impl<'a, W: io::Write> slog_term::RecordDecorator for AnsiRecordDecorator<'a, W> {
+    fn reset(&mut self) -> io::Result<()> {
+        self.io.write_all(b"\x1b[0m")
+    }
+
+    fn start_level(&mut self) -> io::Result<()> {
+        let color = TermDecorator::level_to_color(self.level);
+        write!(self.io, "\x1b[3{}m", color)
+    }
+
+    fn start_key(&mut self) -> io::Result<()> {
+        self.io.write_all(b"\x1b[1m")
+    }
+
+    fn start_msg(&mut self) -> io::Result<()> {
+        self.io.write_all(b"\x1b[1m")
+    }
+}
+
 /// The configuration of `TerminalLoggerBuilder`.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -177,7 +517,19 @@ pub struct TerminalLoggerConfig {
     #[serde(default)]
     pub level: Severity,
 
+    /// Per-module log level filter (e.g., `"info,my_crate::db=debug,hyper=warn"`).
+    ///
+    /// For details, see the documentation of [`ModuleFilter`].
+    ///
+    /// [`ModuleFilter`]: ../types/struct.ModuleFilter.html
+    #[serde(default)]
+    pub filter: Option<ModuleFilter>,
+
     /// Log record format.
+    ///
+    /// The possible values are `full`, `compact`, `json`, or `logfmt`.
+    ///
+    /// The default value is `full`.
     #[serde(default)]
     pub format: Format,
 
@@ -189,7 +541,21 @@ pub struct TerminalLoggerConfig {
     #[serde(default)]
     pub timezone: TimeZone,
 
+    /// Whether to colorize log records.
+    ///
+    /// The possible values are `auto`, `always`, or `never`.
+    ///
+    /// The default value is `auto`.
+    #[serde(default)]
+    pub color: Color,
+
     /// Output destination.
+    ///
+    /// The possible values are `stdout`, `stderr`, `split`, or `split=SEVERITY`.
+    /// With `split`, records at or above the severity (`warning` by default) go to
+    /// the standard error, and the rest to the standard output.
+    ///
+    /// The default value is `stderr`.
     #[serde(default)]
     pub destination: Destination,
 
@@ -204,6 +570,23 @@ pub struct TerminalLoggerConfig {
     /// The default value is `drop_and_report`.
     #[serde(default)]
     pub overflow_strategy: OverflowStrategy,
+
+    /// Key-value filter (e.g., `{ severity = "warning", always_suppress_on_regex = "^debug" }`).
+    ///
+    /// For details, see the documentation of [`KVFilterParameters`].
+    ///
+    /// [`KVFilterParameters`]: ../types/struct.KVFilterParameters.html
+    #[serde(default)]
+    #[cfg(feature = "slog-kvfilter")]
+    pub kvfilter: Option<KVFilterParameters>,
+
+    /// Static key-value pairs attached to all log records (e.g., `{ service = "foo" }`).
+    ///
+    /// The values can contain the following placeholders:
+    /// `{hostname}`, `{pid}`, `{exe}` (the file name of the executable), and
+    /// `${NAME}` (the value of the environment variable `NAME`).
+    #[serde(default)]
+    pub context: BTreeMap<String, String>,
 }
 impl TerminalLoggerConfig {
     /// Creates a new `TerminalLoggerConfig` with default settings.
@@ -216,12 +599,23 @@ impl Config for TerminalLoggerConfig {
     fn try_to_builder(&self) -> Result<Self::Builder> {
         let mut builder = TerminalLoggerBuilder::new();
         builder.level(self.level);
+        if let Some(ref filter) = self.filter {
+            builder.filter(filter.clone());
+        }
         builder.format(self.format);
         builder.source_location(self.source_location);
         builder.timezone(self.timezone);
         builder.destination(self.destination);
+        builder.color(self.color);
         builder.channel_size(self.channel_size);
         builder.overflow_strategy(self.overflow_strategy);
+        #[cfg(feature = "slog-kvfilter")]
+        if let Some(ref parameters) = self.kvfilter {
+            builder.kvfilter(parameters.clone());
+        }
+        for (key, value) in &self.context {
+            builder.context(key, &track!(misc::expand_placeholders(value))?);
+        }
         Ok(builder)
     }
 }
@@ -229,3 +623,92 @@ impl Config for TerminalLoggerConfig {
 fn default_channel_size() -> usize {
     1024
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct Collect(Mutex<Vec<String>>);
+    impl Drain for Collect {
+        type Ok = ();
+        type Err = slog::Never;
+
+        fn log(&self, record: &Record, _: &OwnedKVList) -> result::Result<(), slog::Never> {
+            self.0.lock().unwrap().push(record.msg().to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn destination_config_works() {
+        let config: TerminalLoggerConfig =
+            serdeconv::from_toml_str(r#"destination = "split=error""#).unwrap();
+        assert_eq!(
+            config.destination,
+            Destination::Split {
+                threshold: Severity::Error
+            }
+        );
+        assert_eq!(String::from(config.destination), "split=error");
+        assert!("split=foo".parse::<Destination>().is_err());
+        assert!("both".parse::<Destination>().is_err());
+    }
+
+    #[test]
+    fn split_drain_works() {
+        let drain = SplitDrain {
+            stdout: Collect(Mutex::new(Vec::new())),
+            stderr: Collect(Mutex::new(Vec::new())),
+            threshold: Level::Warning,
+        };
+        let values = o!().into();
+        drain
+            .log(
+                &record!(Level::Info, "", &format_args!("foo"), b!()),
+                &values,
+            )
+            .unwrap();
+        drain
+            .log(
+                &record!(Level::Warning, "", &format_args!("bar"), b!()),
+                &values,
+            )
+            .unwrap();
+        drain
+            .log(
+                &record!(Level::Critical, "", &format_args!("baz"), b!()),
+                &values,
+            )
+            .unwrap();
+
+        assert_eq!(*drain.stdout.0.lock().unwrap(), ["foo"]);
+        assert_eq!(*drain.stderr.0.lock().unwrap(), ["bar", "baz"]);
+    }
+
+    #[test]
+    fn color_config_works() {
+        let config: TerminalLoggerConfig = serdeconv::from_toml_str(r#"color = "always""#).unwrap();
+        assert_eq!(config.color, Color::Always);
+        assert_eq!("never".parse::<Color>().unwrap(), Color::Never);
+        assert!("yes".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn ansi_decorator_works() {
+        let mut buf = Vec::new();
+        let format = FullFormat::new(AnsiDecorator::new(&mut buf)).build();
+        format
+            .log(
+                &record!(Level::Error, "", &format_args!("foo"), b!()),
+                &o!().into(),
+            )
+            .unwrap();
+
+        drop(format);
+
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("\x1b[31mERRO"));
+        assert!(output.contains("\x1b[1mfoo"));
+    }
+}
@@ -3,10 +3,15 @@ use crate::{Error, ErrorKind};
 #[cfg(feature = "slog-kvfilter")]
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use slog::{Drain, Level, LevelFilter};
+use slog::{Drain, Level, LevelFilter, Record};
 #[cfg(feature = "slog-kvfilter")]
 use slog_kvfilter::KVFilterList;
+use std::cmp::Reverse;
+use std::convert::TryFrom;
+use std::fmt;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 /// The severity of a log record.
 ///
@@ -28,11 +33,14 @@ use std::str::FromStr;
 ///
 /// See [slog's documentation](https://docs.rs/slog/2.2.3/slog/#notable-details) for more details.
 #[allow(missing_docs)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
 #[serde(rename_all = "lowercase")]
 pub enum Severity {
     Trace,
     Debug,
+    #[default]
     Info,
     Warning,
     Error,
@@ -51,16 +59,23 @@ impl Severity {
         }
     }
 
+    /// Returns the name of this severity (e.g., `"info"`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Trace => "trace",
+            Severity::Debug => "debug",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+            Severity::Critical => "critical",
+        }
+    }
+
     /// Sets `LevelFilter` to `drain`.
     pub fn set_level_filter<D: Drain>(self, drain: D) -> LevelFilter<D> {
         LevelFilter::new(drain, self.as_level())
     }
 }
-impl Default for Severity {
-    fn default() -> Self {
-        Severity::Info
-    }
-}
 impl FromStr for Severity {
     type Err = Error;
     fn from_str(s: &str) -> Result<Self, Error> {
@@ -76,6 +91,196 @@ impl FromStr for Severity {
     }
 }
 
+/// A handle for changing the log level of loggers at runtime.
+///
+/// The handle can be cloned and shared among threads.
+/// All clones of the handle refer to the same log level, so changing the level via one clone
+/// affects all loggers built with any of them.
+///
+/// # Examples
+///
+/// ```
+/// use sloggers::Build;
+/// use sloggers::terminal::TerminalLoggerBuilder;
+/// use sloggers::types::{LevelHandle, Severity};
+///
+/// let handle = LevelHandle::new(Severity::Info);
+/// let logger = TerminalLoggerBuilder::new()
+///     .level_handle(handle.clone())
+///     .build()
+///     .unwrap();
+///
+/// // Enables debug logging without rebuilding the logger.
+/// handle.set(Severity::Debug);
+/// assert_eq!(handle.get(), Severity::Debug);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LevelHandle(Arc<AtomicUsize>);
+impl LevelHandle {
+    /// Makes a new `LevelHandle` instance which has the given log level.
+    pub fn new(severity: Severity) -> Self {
+        LevelHandle(Arc::new(AtomicUsize::new(severity as usize)))
+    }
+
+    /// Returns the current log level.
+    pub fn get(&self) -> Severity {
+        match self.0.load(Ordering::Relaxed) {
+            0 => Severity::Trace,
+            1 => Severity::Debug,
+            2 => Severity::Info,
+            3 => Severity::Warning,
+            4 => Severity::Error,
+            _ => Severity::Critical,
+        }
+    }
+
+    /// Changes the log level.
+    pub fn set(&self, severity: Severity) {
+        self.0.store(severity as usize, Ordering::Relaxed);
+    }
+}
+impl Default for LevelHandle {
+    fn default() -> Self {
+        Self::new(Severity::default())
+    }
+}
+
+/// Per-module log level filter.
+///
+/// This is built from a comma-separated list of directives like `RUST_LOG` of `env_logger`.
+/// Each directive is one of the following:
+///
+/// - `LEVEL`: Sets the default log level.
+/// - `MODULE=LEVEL`: Sets the log level of the module and its submodules.
+/// - `MODULE`: Enables all log records of the module and its submodules.
+///
+/// `LEVEL` is a [`Severity`] (`warn` is also accepted as `warning`) or `off`.
+///
+/// Log records are matched against their [`module`], and the directive with the longest matching
+/// module path is applied. If there is no matching directive and no default level,
+/// the log level of the logger builder is used.
+///
+/// [`Severity`]: ./enum.Severity.html
+/// [`module`]: https://docs.rs/slog/2/slog/struct.Record.html#method.module
+///
+/// # Examples
+///
+/// ```
+/// use sloggers::types::ModuleFilter;
+///
+/// let filter: ModuleFilter = "info,my_crate::db=debug,hyper=warn".parse().unwrap();
+/// assert_eq!(filter.to_string(), "info,my_crate::db=debug,hyper=warning");
+///
+/// assert!("info,hyper=verbose".parse::<ModuleFilter>().is_err());
+/// ```
+///
+/// Specifying a filter in configuration text (TOML):
+///
+/// ```
+/// use sloggers::LoggerConfig;
+///
+/// let _config: LoggerConfig = serdeconv::from_toml_str(r#"
+/// type = "terminal"
+/// filter = "info,my_crate::db=debug,hyper=warn"
+/// "#).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ModuleFilter {
+    default: Option<Option<Severity>>,
+
+    // Sorted by the length of module paths, longest first.
+    modules: Vec<(String, Option<Severity>)>,
+}
+impl ModuleFilter {
+    /// Returns `true` if `record` passes this filter.
+    ///
+    /// `level` is used if there is neither a matching directive nor a default level.
+    pub fn is_enabled(&self, record: &Record, level: Severity) -> bool {
+        let module = record.module();
+        let severity = self
+            .modules
+            .iter()
+            .find(|(m, _)| {
+                module.starts_with(m.as_str())
+                    && (module.len() == m.len() || module[m.len()..].starts_with("::"))
+            })
+            .map(|(_, s)| *s)
+            .or(self.default)
+            .unwrap_or(Some(level));
+        match severity {
+            Some(s) => record.level().is_at_least(s.as_level()),
+            None => false,
+        }
+    }
+
+    fn parse_level(s: &str) -> Result<Option<Severity>, Error> {
+        match s {
+            "off" => Ok(None),
+            "warn" => Ok(Some(Severity::Warning)),
+            _ => track!(s.parse().map(Some)),
+        }
+    }
+}
+impl FromStr for ModuleFilter {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let mut filter = ModuleFilter {
+            default: None,
+            modules: Vec::new(),
+        };
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let mut parts = directive.splitn(2, '=');
+            let name = parts.next().unwrap_or("").trim();
+            match parts.next().map(str::trim) {
+                Some(level) => {
+                    track_assert!(
+                        !name.is_empty(),
+                        ErrorKind::Invalid,
+                        "Empty module name: {:?}",
+                        directive
+                    );
+                    let level = track!(Self::parse_level(level))?;
+                    filter.modules.push((name.to_owned(), level));
+                }
+                None => match Self::parse_level(name) {
+                    Ok(level) => filter.default = Some(level),
+                    Err(_) => filter.modules.push((name.to_owned(), Some(Severity::Trace))),
+                },
+            }
+        }
+        filter.modules.sort_by_key(|(m, _)| Reverse(m.len()));
+        Ok(filter)
+    }
+}
+impl TryFrom<String> for ModuleFilter {
+    type Error = Error;
+    fn try_from(s: String) -> Result<Self, Error> {
+        s.parse()
+    }
+}
+impl From<ModuleFilter> for String {
+    fn from(filter: ModuleFilter) -> Self {
+        filter.to_string()
+    }
+}
+impl fmt::Display for ModuleFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn level_name(level: Option<Severity>) -> &'static str {
+            level.map_or("off", Severity::as_str)
+        }
+
+        let mut directives = Vec::new();
+        if let Some(level) = self.default {
+            directives.push(level_name(level).to_owned());
+        }
+        for (module, level) in &self.modules {
+            directives.push(format!("{}={}", module, level_name(*level)));
+        }
+        write!(f, "{}", directives.join(","))
+    }
+}
+
 /// Type summarizing KVFilter parameters.
 ///
 /// See the documentation of [`KVFilter`] for more details.
@@ -94,6 +299,24 @@ impl FromStr for Severity {
 /// assert!(params.only_pass_on_regex.is_none());
 /// assert!(params.always_suppress_on_regex.is_none());
 /// ```
+///
+/// Reading the parameters from configuration text (TOML):
+///
+/// ```
+/// use sloggers::types::{KVFilterParameters, Severity};
+///
+/// let params: KVFilterParameters = serdeconv::from_toml_str(r#"
+/// severity = "warning"
+/// only_pass_on_regex = "^(GET|POST) "
+///
+/// [only_pass_any_on_all_keys]
+/// thread = ["main", "worker"]
+/// "#).unwrap();
+/// assert_eq!(params.severity, Severity::Warning);
+/// assert_eq!(params.only_pass_any_on_all_keys.unwrap()["thread"].len(), 2);
+/// assert_eq!(params.only_pass_on_regex.unwrap().as_str(), "^(GET|POST) ");
+/// assert!(params.always_suppress_any.is_none());
+/// ```
 /// 
 /// # Non-Exhaustive
 /// 
@@ -116,15 +339,18 @@ impl FromStr for Severity {
 /// ```
 /// 
 /// [non-exhaustive]: https://doc.rust-lang.org/stable/reference/attributes/type_system.html#the-non_exhaustive-attribute
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(missing_docs)]
 #[cfg(feature = "slog-kvfilter")]
 #[non_exhaustive]
+#[serde(default)]
 pub struct KVFilterParameters {
     pub severity: Severity,
     pub only_pass_any_on_all_keys: Option<KVFilterList>,
     pub always_suppress_any: Option<KVFilterList>,
+    #[serde(with = "regex_serde")]
     pub only_pass_on_regex: Option<Regex>,
+    #[serde(with = "regex_serde")]
     pub always_suppress_on_regex: Option<Regex>,
 }
 #[cfg(feature = "slog-kvfilter")]
@@ -148,6 +374,30 @@ impl KVFilterParameters {
     }
 }
 
+#[cfg(feature = "slog-kvfilter")]
+mod regex_serde {
+    use regex::Regex;
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S>(regex: &Option<Regex>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        regex.as_ref().map(Regex::as_str).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Regex>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let regex: Option<String> = Deserialize::deserialize(deserializer)?;
+        regex
+            .map(|r| Regex::new(&r).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
 /// The format of log records.
 ///
 /// # Examples
@@ -159,20 +409,47 @@ impl KVFilterParameters {
 ///
 /// assert_eq!(Format::default(), Format::Full);
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Selecting a machine-readable format from configuration text (TOML):
+///
+/// ```
+/// use sloggers::LoggerConfig;
+///
+/// let _config: LoggerConfig = serdeconv::from_toml_str(r#"
+/// type = "terminal"
+/// format = "logfmt"
+/// "#).unwrap();
+///
+/// let _config: LoggerConfig = serdeconv::from_toml_str(r#"
+/// type = "file"
+/// path = "/path/to/file.log"
+/// format = "json"
+/// "#).unwrap();
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 #[non_exhaustive]
 pub enum Format {
     /// Full format.
+    #[default]
     Full,
 
     /// Compact format.
     Compact,
-}
-impl Default for Format {
-    fn default() -> Self {
-        Format::Full
-    }
+
+    /// JSON format.
+    ///
+    /// Each log record is written as a single-line JSON object containing
+    /// the `ts`, `level` and `msg` keys, followed by all key-value pairs
+    /// of the logger and the record (e.g., `module`).
+    Json,
+
+    /// [logfmt](https://brandur.org/logfmt) format.
+    ///
+    /// Each log record is written as a single line of `key=value` pairs,
+    /// starting with `ts`, `level` and `msg` (e.g., `ts=... level=info msg="Hello World!"`).
+    /// Values are quoted and escaped if needed.
+    Logfmt,
 }
 impl FromStr for Format {
     type Err = Error;
@@ -180,6 +457,8 @@ impl FromStr for Format {
         match s {
             "full" => Ok(Format::Full),
             "compact" => Ok(Format::Compact),
+            "json" => Ok(Format::Json),
+            "logfmt" => Ok(Format::Logfmt),
             _ => track_panic!(ErrorKind::Invalid, "Undefined log format: {:?}", s),
         }
     }
@@ -197,17 +476,13 @@ impl FromStr for Format {
 /// assert_eq!(TimeZone::default(), TimeZone::Local);
 /// ```
 #[allow(missing_docs)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TimeZone {
     Utc,
+    #[default]
     Local,
 }
-impl Default for TimeZone {
-    fn default() -> Self {
-        TimeZone::Local
-    }
-}
 impl FromStr for TimeZone {
     type Err = Error;
     fn from_str(s: &str) -> Result<Self, Error> {
@@ -231,20 +506,16 @@ impl FromStr for TimeZone {
 /// assert_eq!(SourceLocation::default(), SourceLocation::ModuleAndLine);
 /// ```
 #[allow(missing_docs)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 #[non_exhaustive]
 pub enum SourceLocation {
     None,
+    #[default]
     ModuleAndLine,
     FileAndLine,
     LocalFileAndLine,
 }
-impl Default for SourceLocation {
-    fn default() -> Self {
-        SourceLocation::ModuleAndLine
-    }
-}
 impl FromStr for SourceLocation {
     type Err = Error;
     fn from_str(s: &str) -> Result<Self, Error> {
@@ -274,19 +545,15 @@ impl FromStr for SourceLocation {
 /// assert_eq!(OverflowStrategy::default(), OverflowStrategy::DropAndReport);
 /// ```
 #[allow(missing_docs)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 #[non_exhaustive]
 pub enum OverflowStrategy {
+    #[default]
     DropAndReport,
     Drop,
     Block,
 }
-impl Default for OverflowStrategy {
-    fn default() -> Self {
-        OverflowStrategy::DropAndReport
-    }
-}
 impl FromStr for OverflowStrategy {
     type Err = Error;
     fn from_str(s: &str) -> Result<Self, Error> {
@@ -308,3 +575,56 @@ impl OverflowStrategy {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slog::{RecordLocation, RecordStatic};
+
+    #[test]
+    fn module_filter_works() {
+        fn enabled(filter: &ModuleFilter, module: &'static str, level: Level) -> bool {
+            let location = RecordLocation {
+                file: "",
+                line: 0,
+                column: 0,
+                function: "",
+                module,
+            };
+            let record_static = RecordStatic {
+                location: &location,
+                tag: "",
+                level,
+            };
+            filter.is_enabled(
+                &Record::new(&record_static, &format_args!(""), b!()),
+                Severity::Error,
+            )
+        }
+
+        let filter: ModuleFilter = "info,my_crate::db=debug,hyper=off,my_crate=warn"
+            .parse()
+            .unwrap();
+        assert!(enabled(&filter, "other", Level::Info));
+        assert!(!enabled(&filter, "other", Level::Debug));
+        assert!(enabled(&filter, "my_crate::db", Level::Debug));
+        assert!(enabled(&filter, "my_crate::db::pool", Level::Debug));
+        assert!(!enabled(&filter, "my_crate::dbx", Level::Debug));
+        assert!(!enabled(&filter, "my_crate::dbx", Level::Info));
+        assert!(enabled(&filter, "my_crate::dbx", Level::Warning));
+        assert!(!enabled(&filter, "hyper::client", Level::Critical));
+        assert!(enabled(&filter, "hyperx", Level::Info));
+
+        // Without a default level, the level of the builder is used.
+        let filter: ModuleFilter = "my_crate".parse().unwrap();
+        assert!(enabled(&filter, "my_crate", Level::Trace));
+        assert!(!enabled(&filter, "other", Level::Warning));
+        assert!(enabled(&filter, "other", Level::Error));
+    }
+
+    #[test]
+    fn module_filter_parse_error() {
+        assert!("info,=debug".parse::<ModuleFilter>().is_err());
+        assert!("my_crate=loud".parse::<ModuleFilter>().is_err());
+    }
+}